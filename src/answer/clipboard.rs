@@ -0,0 +1,61 @@
+//! Copy text to system clipboard through OSC 52 escape sequence.
+//!
+//! The sequence is handled by terminal, so it works over ssh and tmux without xclip
+//! or pbcopy installed.  For more information about OSC 52, please check:
+//! https://invisible-island.net/xterm/ctlseqs/ctlseqs.html#h3-Operating-System-Commands
+
+use crate::error::Result;
+use std::env;
+use std::io::{self, Write};
+
+/// Make OSC 52 sequence which sets clipboard content to `text`.
+///
+/// # Arguments
+///
+/// * `text` - the text to copy.
+/// * `in_tmux` - wrap the sequence in tmux passthrough, so tmux sends it to outer terminal.
+pub fn osc52(text: &str, in_tmux: bool) -> String {
+    let sequence: String = format!("\x1b]52;c;{}\x07", base64::encode(text));
+    if in_tmux {
+        // escape characters inside passthrough sequence should be doubled.
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// Copy the given text to clipboard.
+///
+/// The sequence is written to the controlling terminal, so it's not mixed into
+/// piped or paged output.  If the terminal is not available, it's written to stderr.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let sequence: String = osc52(text, env::var("TMUX").is_ok());
+    #[cfg(unix)]
+    {
+        if let Ok(mut tty) = std::fs::OpenOptions::new().write(true).open("/dev/tty") {
+            tty.write_all(sequence.as_bytes())?;
+            return Ok(tty.flush()?);
+        }
+    }
+    let mut stderr = io::stderr();
+    stderr.write_all(sequence.as_bytes())?;
+    Ok(stderr.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(osc52("hello", false), "\x1b]52;c;aGVsbG8=\x07");
+    }
+
+    #[test]
+    fn test_osc52_in_tmux() {
+        assert_eq!(
+            osc52("hello", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGVsbG8=\x07\x1b\\"
+        );
+    }
+}
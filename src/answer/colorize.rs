@@ -7,22 +7,32 @@ use syntect::highlighting::{self, ThemeSet};
 use syntect::parsing::{SyntaxReference, SyntaxSet};
 use syntect::util::LinesWithEndings;
 
+lazy_static! {
+    static ref SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_newlines();
+    static ref THEME_SET: ThemeSet = ThemeSet::load_defaults();
+}
+
 /// make code block colorized.
 ///
 /// Note that this function should only accept code block.
 pub fn colorize_code(code: String, possible_tags: &[String]) -> String {
-    let ss = SyntaxSet::load_defaults_newlines();
-    let ts = ThemeSet::load_defaults();
-    let syntax: &SyntaxReference = guess_syntax(&possible_tags, &ss);
-    let mut h = HighlightLines::new(&syntax, &ts.themes["base16-eighties.dark"]);
+    let ss: &SyntaxSet = &SYNTAX_SET;
+    let syntax: &SyntaxReference = guess_syntax(possible_tags, ss);
+    let mut h = HighlightLines::new(syntax, &THEME_SET.themes["base16-eighties.dark"]);
     let mut colorized: String = String::new();
 
     let is_true_color = is_truecolor_terminal();
     for line in LinesWithEndings::from(code.as_str()) {
-        let highlights = h.highlight(line, &ss);
+        let highlights = h.highlight(line, ss);
         for (style, component) in highlights {
             let color = to_ansi_color(style.foreground, is_true_color);
+            // keep line break out of color, so the caller can decorate each line of code.
+            let (component, line_break) = match component.strip_suffix('\n') {
+                Some(content) => (content, "\n"),
+                None => (component, ""),
+            };
             colorized.push_str(&color.paint(component).to_string());
+            colorized.push_str(line_break);
         }
     }
     colorized
@@ -67,6 +77,21 @@ fn to_ansi_color(color: highlighting::Color, true_color: bool) -> ansi_term::Col
     }
 }
 
+/// Get the language name of code according to the given tags.
+///
+/// # Returns
+///
+/// The language name, e.g: "Rust", or None if we can't find a language for these tags.
+pub fn syntax_name(possible_tags: &[String]) -> Option<String> {
+    let ss: &SyntaxSet = &SYNTAX_SET;
+    let syntax: &SyntaxReference = guess_syntax(possible_tags, ss);
+    if syntax.name == ss.find_syntax_plain_text().name {
+        None
+    } else {
+        Some(syntax.name.clone())
+    }
+}
+
 fn guess_syntax<'a>(possible_tags: &[String], ss: &'a SyntaxSet) -> &'a SyntaxReference {
     for tag in possible_tags {
         let syntax = ss.find_syntax_by_token(tag.as_str());
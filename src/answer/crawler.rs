@@ -3,216 +3,189 @@
 //! Simple usage example:
 //!
 //! ```ignore
-//! use tokio::sync::mpsc;
-//! use crate::config::{Config, OutputOption};
+//! use crate::answer::PageCache;
 //! use reqwest::ClientBuilder;
 //!
-//! let conf = Config::new(OutputOption::All, 1, false);
 //! let client = ClientBuilder::new().cookie_store(true).build().unwrap();
 //!
-//! let (tx, mut rx): (Sender<CrawlerMsg>, Receiver<CrawlerMsg>) = mpsc::channel(10);
-//! let page_crawler = PageCrawler::new(links, conf, client, tx);
-//! page_crawler.fetch();
-//!
-//! while let Some(page) = rx.recv().await {
-//!     match page {
-//!         CrawlerMsg::Done => break,
-//!         CrawlerMsg::Data(m) => {
-//!             // handle for crawled data.
-//!         }
+//! let mut page_crawler = PageCrawler::new(links, client, PageCache::load(), 10);
+//! // fetch more pages until we get what we want.
+//! loop {
+//!     let pages: Vec<CrawledData> = page_crawler.fetch(3).await;
+//!     if pages.is_empty() {
+//!         break;
 //!     }
-//! };
-//!
+//!     for page in pages {
+//!         // handle for crawled data.
+//!     }
+//! }
+//! ```
 
-use super::records::AnswerRecordsCache;
-use crate::config::Config;
+use super::records::PageCache;
+use crate::link::QuestionLink;
 use crate::utils::random_agent;
 use crate::Result;
 use reqwest::{Client, Response};
-use std::process;
-use tokio::sync::mpsc::Sender;
+use std::vec::IntoIter;
 use tokio::task::JoinHandle;
 
 /// Crawler to fetch stackoverflow page
 pub struct PageCrawler {
-    /// so links to fetch relative page.
-    links: Vec<String>,
-    /// current configuration.
-    conf: Config,
+    /// so links to fetch relative page, they're taken in order.
+    links: IntoIter<String>,
+    /// how many links are given.
+    total: usize,
     /// relative page cache, we can use it to avoid too much network traffic.
-    records_cache: AnswerRecordsCache,
+    records_cache: PageCache,
     /// reqwest http client.
     client: Client,
-    /// message sender, which is used to communicate with crawler user.
-    msg_sender: Sender<CrawlerMsg>,
+    /// how many pages can be fetched at most, including pages which fail to fetch.
+    max_pages: usize,
 }
 
 impl PageCrawler {
     pub fn new(
         links: Vec<String>,
-        conf: Config,
         client: Client,
-        msg_sender: Sender<CrawlerMsg>,
+        records_cache: PageCache,
+        max_pages: usize,
     ) -> PageCrawler {
-        debug!("Try to load cache from local cache file.");
-        let load_result: Result<AnswerRecordsCache> = AnswerRecordsCache::load();
-        let records_cache: AnswerRecordsCache = match load_result {
-            Ok(cache) => cache,
-            Err(err) => {
-                warn!("Can't load cache from local cache file, errmsg {:?}", err);
-                AnswerRecordsCache::load_empty()
-            }
-        };
-        debug!("Load cache complete.");
-
         PageCrawler {
-            links,
-            conf,
+            total: links.len(),
+            links: links.into_iter(),
             records_cache,
             client,
-            msg_sender,
+            max_pages,
         }
     }
 
-    /// Consume self to make a concurrent fetch.
+    /// How many links are taken, including links which are skipped.
+    pub fn consumed(&self) -> usize {
+        self.total - self.links.len()
+    }
+
+    /// Fetch pages of the next `count` links concurrently.
+    ///
+    /// Links which are not question or answer are skipped, and pages which fail to fetch
+    /// are dropped, so less pages may be returned, pages are in the same order as links.
+    ///
+    /// # Returns
     ///
-    /// All the fetched pages will be send through `self.msg_sender`.
-    pub fn fetch(mut self) {
-        tokio::spawn(async move {
-            let mut links_iter = self.links.into_iter();
-            let mut tasks = vec![];
-            for _ in 0..self.conf.numbers() {
-                let sender = self.msg_sender.clone();
-                let next_link = links_iter.next();
-                match next_link {
-                    Some(link) => {
-                        // the given links may contains the url doesn't contains `question`
-                        // tag, so it's not a question, just deal with nothing to it.
-                        if !link.contains("question") {
-                            continue;
-                        }
-
-                        // try to fetch page from cache first.
-                        let page_from_cache: Option<&String> = self.records_cache.get(&link);
-                        match page_from_cache {
-                            // When we can get answer from cache, just send the result back to upload task.
-                            Some(page) => {
-                                // send crawled data to other side.
-                                if sender
-                                    .send(CrawlerMsg::Data(CrawledData::new(
-                                        format!("- Answer from {}", &link),
-                                        link,
-                                        page.into(),
-                                    )))
-                                    .await
-                                    .is_err()
-                                {
-                                    error!(
-                                            "Receiver is dropped un-expectly, if you see this message, please fire an issue"
-                                        );
-                                    process::exit(1);
-                                }
-                                continue;
-                            }
-                            None => {
-                                let work_client: Client = self.client.clone();
-                                // spawn task only we can't find page from cache.
-                                let single_fetcher: JoinHandle<Result<CrawledData>> = tokio::spawn(
-                                    async move {
-                                        let page: String = get_page(&link, &work_client).await?;
-                                        let crawled_data = CrawledData::new(
-                                            format!("- Answer from {}", &link),
-                                            link.clone(),
-                                            page,
-                                        );
-
-                                        // send crawled data to other side.
-                                        if sender
-                                            .send(CrawlerMsg::Data(crawled_data.clone()))
-                                            .await
-                                            .is_err()
-                                        {
-                                            error!(
-                                                "Receiver is dropped un-expectly, if you see this message, please fire an issue"
-                                            );
-                                            process::exit(1);
-                                        }
-                                        return Ok(crawled_data);
-                                    },
-                                );
-                                tasks.push(single_fetcher);
-                            }
-                        }
-                    }
-                    None => break,
+    /// Crawled pages, it's empty if there are no more links, or `max_pages` pages are
+    /// fetched.
+    pub async fn fetch(&mut self, count: usize) -> Vec<CrawledData> {
+        let count: usize = count.min(self.max_pages);
+        // crawled data, along with whether it's fetched from network.
+        let mut tasks: Vec<JoinHandle<Result<(CrawledData, bool)>>> = vec![];
+        // the given links may contains the url which is not a question or answer link,
+        // just deal with nothing to it.
+        for link in self
+            .links
+            .by_ref()
+            .filter(|link| QuestionLink::parse(link).is_some())
+            .take(count)
+        {
+            let records_cache: PageCache = self.records_cache.clone();
+            let work_client: Client = self.client.clone();
+            tasks.push(tokio::spawn(async move {
+                // try to fetch page from cache first.
+                if let Some(page) = records_cache.get(&link) {
+                    return Ok((CrawledData::new(link, page), false));
                 }
-            }
+                let page: String = get_page(&link, &work_client).await?;
+                records_cache.put(link.clone(), page.clone());
+                Ok((CrawledData::new(link, page), true))
+            }));
+        }
+        // links may run out before `count`, only pages which are actually fetched count.
+        self.max_pages -= tasks.len();
+        if tasks.is_empty() {
+            return vec![];
+        }
 
-            // Wait for all sub-tasks complete, push result to our cache.
-            for t in tasks {
-                // Ignore any sub tasks error.
-                if let Ok(crawled_result) = t.await {
-                    if let Ok(crawled_data) = crawled_result {
-                        let (link, page) = crawled_data.into_cache_item();
-                        self.records_cache.put(link, page);
-                    }
+        // Wait for all sub-tasks complete in order of links.
+        let mut pages: Vec<CrawledData> = vec![];
+        let mut cache_updated: bool = false;
+        for t in tasks {
+            match t.await {
+                Ok(Ok((crawled_data, fetched))) => {
+                    cache_updated |= fetched;
+                    pages.push(crawled_data);
                 }
+                Ok(Err(err)) => warn!("Fetch page failed, error msg: {:?}", err),
+                Err(err) => warn!("Fetch task failed, error msg: {:?}", err),
             }
+        }
 
-            // when hors gets what we wanted answer, save cache for next time using.
+        // save cache for next time using.
+        if cache_updated {
             if let Err(err) = self.records_cache.save() {
                 warn!(
                     "Can't save cache into local directory, error msg: {:?}",
                     err
                 );
             }
-
-            // Notification done message.
-            if self.msg_sender.send(CrawlerMsg::Done).await.is_err() {
-                error!(
-                    "Receiver is dropped un-expectly, if you see this message, please fire an issue"
-                );
-                process::exit(1);
-            }
-        });
+        }
+        pages
     }
 }
 
-/// The message which is used to communicate with other task.
-#[derive(Debug, Clone)]
-pub enum CrawlerMsg {
-    Data(CrawledData),
-    Done,
-}
-
 /// Data crawled by our crawler
 #[derive(Debug, Clone)]
 pub struct CrawledData {
     link: String,
-    title: String,
     page: String,
+    /// id of the answer which the link points to.
+    answer_id: Option<String>,
 }
 
 impl CrawledData {
-    pub fn new(title: String, link: String, page: String) -> CrawledData {
-        CrawledData { link, title, page }
+    pub fn new(link: String, page: String) -> CrawledData {
+        let answer_id: Option<String> = linked_answer_id(&link);
+        CrawledData {
+            link,
+            page,
+            answer_id,
+        }
     }
 
-    pub fn get_title(&self) -> &str {
-        &self.title
+    pub fn get_link(&self) -> &str {
+        &self.link
     }
 
-    pub fn get_link(&self) -> &str {
-        &self.title
+    pub fn get_answer_id(&self) -> Option<&str> {
+        self.answer_id.as_deref()
     }
 
     pub fn get_page(&self) -> &str {
         &self.page
     }
+}
+
+/// Fetch a single page, the page is taken from cache if possible.
+pub async fn fetch_page(link: &str, client: &Client, records_cache: &PageCache) -> Result<String> {
+    if let Some(page) = records_cache.get(link) {
+        return Ok(page);
+    }
 
-    pub fn into_cache_item(self) -> (String, String) {
-        (self.link, self.page)
+    let page: String = get_page(link, client).await?;
+    records_cache.put(link.to_string(), page.clone());
+    if let Err(err) = records_cache.save() {
+        warn!(
+            "Can't save cache into local directory, error msg: {:?}",
+            err
+        );
     }
+    Ok(page)
+}
+
+/// Get id of the answer which the link points to.
+///
+/// The link looks like `/a/67890` or `/questions/12345/slug#67890`.
+pub fn linked_answer_id(link: &str) -> Option<String> {
+    QuestionLink::parse(link)
+        .and_then(|link| link.answer_id())
+        .map(|answer_id| answer_id.to_string())
 }
 
 async fn get_page(link: &str, client: &Client) -> Result<String> {
@@ -225,3 +198,75 @@ async fn get_page(link: &str, client: &Client) -> Result<String> {
     let page: String = resp.text().await?;
     Ok(page)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_pages_in_order() {
+        let records_cache: PageCache = PageCache::empty();
+        let links: Vec<String> = (1..=5)
+            .map(|id| format!("https://stackoverflow.com/questions/{}", id))
+            .collect();
+        for link in links.iter() {
+            records_cache.put(link.clone(), format!("page of {}", link));
+        }
+        let mut crawler_links: Vec<String> = links.clone();
+        crawler_links.insert(1, String::from("https://stackoverflow.com/tags/json"));
+        let mut crawler = PageCrawler::new(crawler_links, Client::new(), records_cache, 4);
+
+        // the tag link is skipped, and doesn't take a place.
+        let pages: Vec<CrawledData> = crawler.fetch(3).await;
+        assert_eq!(
+            pages.iter().map(|m| m.get_link()).collect::<Vec<&str>>(),
+            vec![&links[0], &links[1], &links[2]]
+        );
+        assert_eq!(pages[0].get_page(), format!("page of {}", links[0]));
+        // at most 4 pages can be fetched.
+        let pages: Vec<CrawledData> = crawler.fetch(3).await;
+        assert_eq!(
+            pages.iter().map(|m| m.get_link()).collect::<Vec<&str>>(),
+            vec![&links[3]]
+        );
+        assert_eq!(crawler.consumed(), 5);
+        assert!(crawler.fetch(3).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_when_links_run_out() {
+        let records_cache: PageCache = PageCache::empty();
+        let links: Vec<String> = vec![
+            String::from("https://stackoverflow.com/questions/1"),
+            String::from("https://stackoverflow.com/tags/json"),
+            String::from("https://stackoverflow.com/questions/2"),
+        ];
+        for link in links.iter() {
+            records_cache.put(link.clone(), format!("page of {}", link));
+        }
+        let mut crawler = PageCrawler::new(links, Client::new(), records_cache, 5);
+
+        // only 2 pages are fetched, so the rest of max pages are kept.
+        assert_eq!(crawler.fetch(4).await.len(), 2);
+        assert_eq!(crawler.max_pages, 3);
+        assert_eq!(crawler.consumed(), 3);
+        assert!(crawler.fetch(4).await.is_empty());
+        assert_eq!(crawler.max_pages, 3);
+    }
+
+    #[test]
+    fn test_linked_answer_id() {
+        assert_eq!(
+            linked_answer_id("https://stackoverflow.com/a/67890"),
+            Some(String::from("67890"))
+        );
+        assert_eq!(
+            linked_answer_id("https://stackoverflow.com/questions/12345/parse-json#67890"),
+            Some(String::from("67890"))
+        );
+        assert_eq!(
+            linked_answer_id("https://stackoverflow.com/questions/12345/parse-json"),
+            None
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Export answers as notes (markdown, org, html), along with the attribution which is
+//! required by CC BY-SA license of Stack Exchange content.
+//!
+//! For more information about the attribution, please check:
+//! https://stackoverflow.com/help/licensing
+
+use crate::config::ExportFormat;
+
+/// Inline content of text block.
+#[derive(Debug, PartialEq)]
+pub enum Inline {
+    Text(String),
+    Code(String),
+    Link { text: String, url: String },
+}
+
+/// A block of answer, which is either a paragraph or a code block.
+#[derive(Debug, PartialEq)]
+pub enum Block {
+    Text(Vec<Inline>),
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+}
+
+/// Information which is needed to attribute the answer.
+#[derive(Debug)]
+pub struct Attribution<'a> {
+    /// question title.
+    pub title: &'a str,
+    /// question link.
+    pub link: &'a str,
+    /// answer permalink.
+    pub permalink: &'a str,
+    /// display name of answer author.
+    pub author: Option<&'a str>,
+    /// link to author profile.
+    pub author_link: Option<&'a str>,
+    /// when the answer is edited lastly (or created), formatted as `yyyy-mm-dd`.
+    pub date: Option<&'a str>,
+    /// the question which is closed as duplicate of this question.
+    pub redirected_from: Option<&'a str>,
+}
+
+/// Export an answer with the given format.
+pub fn export_answer(format: ExportFormat, blocks: &[Block], attribution: &Attribution) -> String {
+    let body: String = blocks
+        .iter()
+        .map(|block| render_block(format, block))
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    let (license, license_url): (&str, &str) = license(attribution.date);
+    let link = |url: &str, text: &str| -> String { render_inline(format, &link_inline(url, text)) };
+
+    let mut footer: Vec<String> = vec![format!(
+        "Source: {}",
+        link(attribution.permalink, attribution.permalink)
+    )];
+    if let Some(duplicate) = attribution.redirected_from {
+        footer.push(format!(
+            "Redirected from: {} (closed as duplicate)",
+            link(duplicate, duplicate)
+        ));
+    }
+    if let Some(author) = attribution.author {
+        let author: String = match attribution.author_link {
+            Some(author_link) => link(author_link, author),
+            None => render_inline(format, &Inline::Text(author.to_string())),
+        };
+        footer.push(format!("Author: {}", author));
+    }
+    footer.push(format!("License: {}", link(license_url, license)));
+
+    let title: String = link(attribution.link, attribution.title);
+    match format {
+        ExportFormat::Markdown => format!(
+            "## {}\n\n{}\n\n---\n\n{}\n",
+            title,
+            body,
+            footer
+                .iter()
+                .map(|line| format!("- {}", line))
+                .collect::<Vec<String>>()
+                .join("\n")
+        ),
+        ExportFormat::Org => format!(
+            "* {}\n\n{}\n\n-----\n{}\n",
+            title,
+            body,
+            footer
+                .iter()
+                .map(|line| format!("- {}", line))
+                .collect::<Vec<String>>()
+                .join("\n")
+        ),
+        ExportFormat::Html => format!(
+            "<article>\n<h2>{}</h2>\n{}\n<footer>\n<p>{}</p>\n</footer>\n</article>\n",
+            title,
+            body,
+            footer.join("<br>\n")
+        ),
+    }
+}
+
+/// Make a complete document from exported answers.
+pub fn export_document(format: ExportFormat, answers: &[String]) -> String {
+    match format {
+        ExportFormat::Markdown | ExportFormat::Org => answers.join("\n"),
+        ExportFormat::Html => format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>hors answers</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+            answers.join("\n")
+        ),
+    }
+}
+
+/// Get the license of Stack Exchange content according to the date it's contributed.
+///
+/// # Returns
+///
+/// License name and license link.
+fn license(date: Option<&str>) -> (&'static str, &'static str) {
+    match date {
+        Some(date) if date < "2011-04-08" => (
+            "CC BY-SA 2.5",
+            "https://creativecommons.org/licenses/by-sa/2.5/",
+        ),
+        Some(date) if date < "2018-05-02" => (
+            "CC BY-SA 3.0",
+            "https://creativecommons.org/licenses/by-sa/3.0/",
+        ),
+        _ => (
+            "CC BY-SA 4.0",
+            "https://creativecommons.org/licenses/by-sa/4.0/",
+        ),
+    }
+}
+
+fn link_inline(url: &str, text: &str) -> Inline {
+    Inline::Link {
+        text: text.to_string(),
+        url: url.to_string(),
+    }
+}
+
+fn render_block(format: ExportFormat, block: &Block) -> String {
+    match block {
+        Block::Text(inlines) => {
+            let text: String = inlines
+                .iter()
+                .map(|inline| render_inline(format, inline))
+                .collect();
+            let text: &str = text.trim();
+            match format {
+                ExportFormat::Html if !text.is_empty() => format!("<p>{}</p>", text),
+                _ => text.to_string(),
+            }
+        }
+        Block::Code { language, code } => {
+            let code: &str = code.trim_end_matches('\n');
+            match (format, language) {
+                (ExportFormat::Markdown, language) => {
+                    // fence should be longer than backticks inside code.
+                    let fence: String = "`".repeat(longest_backticks(code).max(2) + 1);
+                    format!(
+                        "{}{}\n{}\n{}",
+                        fence,
+                        language.as_deref().unwrap_or_default(),
+                        code,
+                        fence
+                    )
+                }
+                (ExportFormat::Org, Some(language)) => {
+                    format!("#+BEGIN_SRC {}\n{}\n#+END_SRC", language, code)
+                }
+                (ExportFormat::Org, None) => format!("#+BEGIN_EXAMPLE\n{}\n#+END_EXAMPLE", code),
+                (ExportFormat::Html, Some(language)) => format!(
+                    "<pre><code class=\"language-{}\">{}</code></pre>",
+                    escape_html(language),
+                    escape_html(code)
+                ),
+                (ExportFormat::Html, None) => {
+                    format!("<pre><code>{}</code></pre>", escape_html(code))
+                }
+            }
+        }
+    }
+}
+
+fn render_inline(format: ExportFormat, inline: &Inline) -> String {
+    match (format, inline) {
+        (ExportFormat::Html, Inline::Text(text)) => escape_html(text),
+        (_, Inline::Text(text)) => text.clone(),
+        (ExportFormat::Markdown, Inline::Code(code)) => {
+            let delimiter: String = "`".repeat(longest_backticks(code) + 1);
+            // code which starts or ends with backtick is padded, or it merges into delimiter.
+            if code.starts_with('`') || code.ends_with('`') {
+                format!("{} {} {}", delimiter, code, delimiter)
+            } else {
+                format!("{}{}{}", delimiter, code, delimiter)
+            }
+        }
+        (ExportFormat::Org, Inline::Code(code)) => format!("~{}~", code),
+        (ExportFormat::Html, Inline::Code(code)) => format!("<code>{}</code>", escape_html(code)),
+        (ExportFormat::Markdown, Inline::Link { text, url }) => format!("[{}]({})", text, url),
+        (ExportFormat::Org, Inline::Link { text, url }) => format!("[[{}][{}]]", url, text),
+        (ExportFormat::Html, Inline::Link { text, url }) => {
+            format!("<a href=\"{}\">{}</a>", escape_html(url), escape_html(text))
+        }
+    }
+}
+
+/// Get length of the longest run of backticks in text.
+fn longest_backticks(text: &str) -> usize {
+    text.split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or_default()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocks() -> Vec<Block> {
+        vec![
+            Block::Text(vec![
+                Inline::Text(String::from("Use ")),
+                Inline::Code(String::from("json.loads")),
+                Inline::Text(String::from(", see ")),
+                link_inline("https://docs.python.org", "docs"),
+            ]),
+            Block::Code {
+                language: Some(String::from("python")),
+                code: String::from("data = json.loads(s)\n"),
+            },
+        ]
+    }
+
+    fn attribution() -> Attribution<'static> {
+        Attribution {
+            title: "How to parse json?",
+            link: "https://stackoverflow.com/questions/1/how-to-parse-json",
+            permalink: "https://stackoverflow.com/a/2",
+            author: Some("John"),
+            author_link: Some("https://stackoverflow.com/users/3"),
+            date: Some("2012-01-05"),
+            redirected_from: None,
+        }
+    }
+
+    #[test]
+    fn test_export_markdown() {
+        assert_eq!(
+            export_answer(ExportFormat::Markdown, &blocks(), &attribution()),
+            "## [How to parse json?](https://stackoverflow.com/questions/1/how-to-parse-json)
+
+Use `json.loads`, see [docs](https://docs.python.org)
+
+```python
+data = json.loads(s)
+```
+
+---
+
+- Source: [https://stackoverflow.com/a/2](https://stackoverflow.com/a/2)
+- Author: [John](https://stackoverflow.com/users/3)
+- License: [CC BY-SA 3.0](https://creativecommons.org/licenses/by-sa/3.0/)
+"
+        );
+    }
+
+    #[test]
+    fn test_export_markdown_code_with_backticks() {
+        let blocks = vec![
+            Block::Text(vec![Inline::Code(String::from("`a`"))]),
+            Block::Code {
+                language: Some(String::from("markdown")),
+                code: String::from("```rust\nlet a = 1;\n```"),
+            },
+        ];
+        let exported: String = export_answer(ExportFormat::Markdown, &blocks, &attribution());
+        assert!(exported.contains("`` `a` ``"));
+        assert!(exported.contains("````markdown\n```rust\nlet a = 1;\n```\n````\n"));
+    }
+
+    #[test]
+    fn test_export_redirected_answer() {
+        let mut attribution = attribution();
+        attribution.redirected_from = Some("https://stackoverflow.com/questions/4");
+        let exported: String = export_answer(ExportFormat::Markdown, &blocks(), &attribution);
+        assert!(exported.contains(
+            "- Source: [https://stackoverflow.com/a/2](https://stackoverflow.com/a/2)\n\
+             - Redirected from: [https://stackoverflow.com/questions/4](https://stackoverflow.com/questions/4) (closed as duplicate)\n"
+        ));
+    }
+
+    #[test]
+    fn test_export_org() {
+        let exported: String = export_answer(ExportFormat::Org, &blocks(), &attribution());
+        assert!(exported.starts_with(
+            "* [[https://stackoverflow.com/questions/1/how-to-parse-json][How to parse json?]]"
+        ));
+        assert!(exported.contains("Use ~json.loads~, see [[https://docs.python.org][docs]]"));
+        assert!(exported.contains("#+BEGIN_SRC python\ndata = json.loads(s)\n#+END_SRC"));
+        assert!(exported.contains(
+            "- License: [[https://creativecommons.org/licenses/by-sa/3.0/][CC BY-SA 3.0]]"
+        ));
+    }
+
+    #[test]
+    fn test_export_html() {
+        let blocks = vec![Block::Code {
+            language: None,
+            code: String::from("if a < b {}"),
+        }];
+        let mut attribution = attribution();
+        attribution.author_link = None;
+        let exported: String = export_answer(ExportFormat::Html, &blocks, &attribution);
+        assert!(exported.contains("<pre><code>if a &lt; b {}</code></pre>"));
+        assert!(exported.contains("Author: John<br>"));
+
+        let document: String = export_document(ExportFormat::Html, &[exported]);
+        assert!(document.starts_with("<!DOCTYPE html>"));
+        assert!(document.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn test_license() {
+        assert_eq!(license(Some("2010-01-01")).0, "CC BY-SA 2.5");
+        assert_eq!(license(Some("2018-05-01")).0, "CC BY-SA 3.0");
+        assert_eq!(license(Some("2018-05-02")).0, "CC BY-SA 4.0");
+        assert_eq!(license(None).0, "CC BY-SA 4.0");
+    }
+}
@@ -0,0 +1,298 @@
+//! History of queries, so user can find and re-run queries later.
+//!
+//! Every query is appended as a json line into history file in data directory, along
+//! with the search engine, the links of shown answers and the outcome.
+
+use crate::error::{Error, Result};
+use crate::utils::data_dir;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File name of query history in data directory.
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// How a query ended up.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    /// answers are shown.
+    Answered,
+    /// search results are listed, but no answer is loaded.
+    Browsed,
+    /// search engine doesn't find any question.
+    NoResult,
+    /// search or fetching answers failed.
+    Failed,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Answered => "answered",
+            Outcome::Browsed => "browsed",
+            Outcome::NoResult => "no result",
+            Outcome::Failed => "failed",
+        }
+    }
+}
+
+/// A query in history.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// when the query is made, as seconds since unix epoch.
+    pub time: u64,
+    /// user input query.
+    pub query: String,
+    /// search engine which is used, like `bing`.
+    pub engine: String,
+    /// links of the shown answers.
+    pub links: Vec<String>,
+    /// how the query ended up.
+    pub outcome: Outcome,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        query: String,
+        engine: String,
+        links: Vec<String>,
+        outcome: Outcome,
+    ) -> HistoryEntry {
+        HistoryEntry {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Time went beckwards")
+                .as_secs(),
+            query,
+            engine,
+            links,
+            outcome,
+        }
+    }
+
+    /// Return true if query or links of the entry contain the given keyword, case is ignored.
+    pub fn contains(&self, keyword: &str) -> bool {
+        let keyword: String = keyword.to_lowercase();
+        self.query.to_lowercase().contains(&keyword)
+            || self
+                .links
+                .iter()
+                .any(|link| link.to_lowercase().contains(&keyword))
+    }
+
+    /// Format query time as `yyyy-mm-dd HH:MM` in UTC.
+    pub fn format_time(&self) -> String {
+        let days: i64 = (self.time / 86400) as i64;
+        let seconds: u64 = self.time % 86400;
+        let (year, month, day) = civil_from_days(days);
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            year,
+            month,
+            day,
+            seconds / 3600,
+            seconds % 3600 / 60
+        )
+    }
+}
+
+/// Reference to history entry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryRef {
+    /// `!N`, the N-th (starts from 1) entry.
+    Number(usize),
+    /// `!!`, the latest entry.
+    Last,
+}
+
+impl HistoryRef {
+    /// Parse history reference from user input.
+    ///
+    /// # Returns
+    ///
+    /// The reference, or None if input is not a history reference.
+    pub fn parse(input: &str) -> Option<HistoryRef> {
+        match input.trim().strip_prefix('!')? {
+            "!" => Some(HistoryRef::Last),
+            number => number.parse().ok().map(HistoryRef::Number),
+        }
+    }
+}
+
+/// Query history, entries are ordered by time.
+#[derive(Debug, Default, PartialEq)]
+pub struct History(Vec<HistoryEntry>);
+
+impl History {
+    /// Load history from data directory.
+    ///
+    /// # Returns
+    ///
+    /// The history, it's empty if nothing is searched yet.
+    pub fn load() -> Result<History> {
+        Self::load_from(&Self::get_history_path()?)
+    }
+
+    /// Load history from the given file, lines which can't be parsed are skipped.
+    pub fn load_from(path: &Path) -> Result<History> {
+        if !path.exists() {
+            return Ok(History::default());
+        }
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries: Vec<HistoryEntry> = Vec::new();
+        for line in reader.lines() {
+            match serde_json::from_str(&line?) {
+                Ok(entry) => entries.push(entry),
+                Err(err) => warn!("Skip invalid history entry, error msg: {:?}", err),
+            }
+        }
+        Ok(History(entries))
+    }
+
+    /// Append entry into history file in data directory.
+    pub fn append(entry: &HistoryEntry) -> Result<()> {
+        let path: PathBuf = Self::get_history_path()?;
+        if let Some(history_dir) = path.parent() {
+            fs::create_dir_all(history_dir)?;
+        }
+        Self::append_to(&path, entry)
+    }
+
+    /// Append entry into the given history file.
+    pub fn append_to(path: &Path, entry: &HistoryEntry) -> Result<()> {
+        let line: String = serde_json::to_string(entry)
+            .map_err(|_| Error::from_parse("Serialize history entry failed"))?;
+        let mut f = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(f, "{}", line)?;
+        Ok(())
+    }
+
+    /// Get entry by number (starts from 1).
+    pub fn get(&self, number: usize) -> Option<&HistoryEntry> {
+        number.checked_sub(1).and_then(|index| self.0.get(index))
+    }
+
+    /// Get the latest entry.
+    pub fn last(&self) -> Option<&HistoryEntry> {
+        self.0.last()
+    }
+
+    /// Get entry which is referred by the given reference.
+    pub fn get_by_ref(&self, reference: HistoryRef) -> Option<&HistoryEntry> {
+        match reference {
+            HistoryRef::Number(number) => self.get(number),
+            HistoryRef::Last => self.last(),
+        }
+    }
+
+    /// Iterate over entries along with their numbers (starts from 1).
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &HistoryEntry)> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| (index + 1, entry))
+    }
+
+    /// Search entries whose query or links contain the keyword.
+    pub fn search<'a>(
+        &'a self,
+        keyword: &'a str,
+    ) -> impl Iterator<Item = (usize, &'a HistoryEntry)> {
+        self.iter()
+            .filter(move |(_, entry)| entry.contains(keyword))
+    }
+
+    fn get_history_path() -> Result<PathBuf> {
+        let mut path: PathBuf = data_dir()?;
+        path.push(HISTORY_FILE);
+        Ok(path)
+    }
+}
+
+/// Convert days since unix epoch to (year, month, day).
+///
+/// The algorithm comes from http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z: i64 = days + 719468;
+    let era: i64 = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe: i64 = z - era * 146097;
+    let yoe: i64 = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy: i64 = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp: i64 = (5 * doy + 2) / 153;
+    let day: u32 = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month: u32 = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year: i64 = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn entry(query: &str, link: &str) -> HistoryEntry {
+        HistoryEntry::new(
+            query.to_string(),
+            String::from("bing"),
+            vec![link.to_string()],
+            Outcome::Answered,
+        )
+    }
+
+    #[test]
+    fn test_history_append_and_load() {
+        let path: PathBuf =
+            env::temp_dir().join(format!("hors_history_test_{}", std::process::id()));
+        let first = entry(
+            "rust lifetimes",
+            "https://stackoverflow.com/questions/1/lifetimes",
+        );
+        let second = entry("parse json", "https://stackoverflow.com/questions/2/json");
+        History::append_to(&path, &first).unwrap();
+        History::append_to(&path, &second).unwrap();
+        let history: History = History::load_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(history.get(1), Some(&first));
+        assert_eq!(history.get(0), None);
+        assert_eq!(history.get_by_ref(HistoryRef::Last), Some(&second));
+    }
+
+    #[test]
+    fn test_history_search() {
+        let history = History(vec![
+            entry(
+                "rust lifetimes",
+                "https://stackoverflow.com/questions/1/lifetimes",
+            ),
+            entry(
+                "parse json",
+                "https://stackoverflow.com/questions/2/serde-json",
+            ),
+        ]);
+        let numbers: Vec<usize> = history.search("LIFETIME").map(|(n, _)| n).collect();
+        assert_eq!(numbers, vec![1]);
+        let numbers: Vec<usize> = history.search("serde").map(|(n, _)| n).collect();
+        assert_eq!(numbers, vec![2]);
+    }
+
+    #[test]
+    fn test_history_ref_parse() {
+        assert_eq!(HistoryRef::parse("!3"), Some(HistoryRef::Number(3)));
+        assert_eq!(HistoryRef::parse("!!"), Some(HistoryRef::Last));
+        assert_eq!(HistoryRef::parse("!important"), None);
+        assert_eq!(HistoryRef::parse("rust lifetimes"), None);
+    }
+
+    #[test]
+    fn test_format_time() {
+        let mut entry = entry("parse json", "");
+        entry.time = 0;
+        assert_eq!(entry.format_time(), "1970-01-01 00:00");
+        entry.time = 1_709_210_096;
+        assert_eq!(entry.format_time(), "2024-02-29 12:34");
+    }
+}
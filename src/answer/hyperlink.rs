@@ -0,0 +1,64 @@
+//! Make clickable links in terminal through OSC 8 escape sequence.
+//!
+//! For more information about OSC 8, please check:
+//! https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda
+
+use std::env;
+use std::io::{self, IsTerminal};
+
+/// Make the given text a hyperlink to `url`.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Return true if the current running terminal supports OSC 8 hyperlinks.
+///
+/// `$FORCE_HYPERLINK` can be used to override the detection, e.g: `FORCE_HYPERLINK=1`.
+pub fn is_hyperlink_terminal() -> bool {
+    // Note: the detection mainly follows the supports-hyperlinks crate:
+    // https://github.com/zkat/supports-hyperlinks
+    if let Ok(force) = env::var("FORCE_HYPERLINK") {
+        return force.trim() != "0";
+    }
+    if !io::stdout().is_terminal() || env::var("CI").is_ok() {
+        return false;
+    }
+    if env::var("DOMTERM").is_ok() || env::var("WT_SESSION").is_ok() {
+        return true;
+    }
+    if env::var("KONSOLE_VERSION").is_ok() {
+        return true;
+    }
+    // VTE based terminals (e.g: gnome-terminal) support hyperlinks since 0.50.
+    if let Some(version) = env::var("VTE_VERSION")
+        .ok()
+        .and_then(|version| version.parse::<u32>().ok())
+    {
+        return version >= 5000;
+    }
+    if let Ok(program) = env::var("TERM_PROGRAM") {
+        if ["iTerm.app", "WezTerm", "vscode", "Hyper", "ghostty"].contains(&program.as_str()) {
+            return true;
+        }
+    }
+    env::var("TERM")
+        .map(|term| {
+            ["kitty", "alacritty", "foot", "ghostty"]
+                .iter()
+                .any(|name| term.contains(name))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyperlink() {
+        assert_eq!(
+            hyperlink("https://stackoverflow.com/a/1", "answer"),
+            "\x1b]8;;https://stackoverflow.com/a/1\x1b\\answer\x1b]8;;\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Remember the last query and it's links, so user can get the following answers
+//! without searching again.
+
+use crate::engine::SearchHit;
+use crate::error::Result;
+use crate::utils::{cache_dir, read_json, write_json};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// File name of the last query in cache directory.
+const LAST_QUERY_FILE: &str = "last_query.json";
+
+/// The last query which user searched.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct LastQuery {
+    /// user input query.
+    pub query: String,
+    /// the links which are searched by query.
+    pub links: Vec<String>,
+    /// the position (starts from 1) of link which is not shown yet.
+    pub next_position: usize,
+    /// search hits of links, they're used to display titles and snippets.
+    #[serde(default)]
+    pub hits: Vec<SearchHit>,
+}
+
+impl LastQuery {
+    pub fn new(query: String, links: Vec<String>, next_position: usize) -> LastQuery {
+        LastQuery {
+            query,
+            links,
+            next_position,
+            hits: Vec::new(),
+        }
+    }
+
+    pub fn with_hits(mut self, hits: Vec<SearchHit>) -> LastQuery {
+        self.hits = hits;
+        self
+    }
+
+    /// Save the last query into cache directory.
+    pub fn save(&self) -> Result<()> {
+        let mut path: PathBuf = cache_dir()?;
+        fs::create_dir_all(&path)?;
+        path.push(LAST_QUERY_FILE);
+        write_json(&path, self)
+    }
+
+    /// Load the last query.
+    ///
+    /// # Returns
+    ///
+    /// The last query, or None if user doesn't search anything yet.
+    pub fn load() -> Result<Option<LastQuery>> {
+        let mut path: PathBuf = cache_dir()?;
+        path.push(LAST_QUERY_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        read_json(&path).map(Some)
+    }
+
+    /// Get links which start from the given position (starts from 1).
+    ///
+    /// # Returns
+    ///
+    /// The links, it's empty if position is out of range.
+    pub fn links_from(&self, position: usize) -> &[String] {
+        self.links.get(position.max(1) - 1..).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_query() -> LastQuery {
+        LastQuery::new(
+            String::from("parse json"),
+            vec![String::from("a"), String::from("b"), String::from("c")],
+            2,
+        )
+    }
+
+    #[test]
+    fn test_links_from() {
+        let last_query = last_query();
+        assert_eq!(last_query.links_from(1).len(), 3);
+        assert_eq!(
+            last_query.links_from(2),
+            &[String::from("b"), String::from("c")]
+        );
+        assert_eq!(last_query.links_from(0).len(), 3);
+    }
+
+    #[test]
+    fn test_links_from_when_position_is_out_of_range() {
+        let last_query = last_query();
+        assert!(last_query.links_from(4).is_empty());
+        assert!(last_query.links_from(10).is_empty());
+    }
+
+    #[test]
+    fn test_load_last_query_without_hits() {
+        let last_query: LastQuery =
+            serde_json::from_str(r#"{"query": "parse json", "links": ["a"], "next_position": 2}"#)
+                .unwrap();
+        assert!(last_query.hits.is_empty());
+    }
+}
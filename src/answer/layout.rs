@@ -0,0 +1,231 @@
+//! Layout answer text for terminal output.
+//!
+//! It wraps answer text to a given width, and makes code block visually separated from
+//! the rest of answer text.
+
+use crate::config::CodeStyle;
+use unicode_width::UnicodeWidthChar;
+
+/// Indentation for code blocks under `CodeStyle::Indent`.
+const CODE_INDENT: &str = "    ";
+
+/// Wrap text to the given width.
+///
+/// The existing line breaks and the leading indentation of each line are kept, and words
+/// which are longer than `width` are put into their own line.
+///
+/// # Arguments
+///
+/// * `text` - the text to wrap.
+/// * `width` - the maximum width of a line, None means don't wrap.
+pub fn wrap_text(text: &str, width: Option<usize>) -> String {
+    let width: usize = match width {
+        Some(width) => width,
+        None => return text.to_string(),
+    };
+
+    let mut wrapped_lines: Vec<String> = Vec::new();
+    for line in text.split('\n') {
+        if display_width(line) <= width {
+            wrapped_lines.push(line.to_string());
+            continue;
+        }
+
+        let content = line.trim_start();
+        let indent: &str = &line[..line.len() - content.len()];
+        let mut current: String = indent.to_string();
+        let mut current_width: usize = display_width(indent);
+        let mut is_empty = true;
+        for word in content.split_whitespace() {
+            let word_width = display_width(word);
+            if !is_empty && current_width + 1 + word_width > width {
+                wrapped_lines.push(current);
+                current = indent.to_string();
+                current_width = display_width(indent);
+                is_empty = true;
+            }
+            if !is_empty {
+                current.push(' ');
+                current_width += 1;
+            }
+            current.push_str(word);
+            current_width += word_width;
+            is_empty = false;
+        }
+        wrapped_lines.push(current);
+    }
+    wrapped_lines.join("\n")
+}
+
+/// Format code block with the given style.
+///
+/// # Arguments
+///
+/// * `code` - the code to format, it may be colorized already.
+/// * `label` - language label which is displayed above the code, it's ignored by `CodeStyle::Plain`.
+/// * `style` - how to display code block.
+/// * `line_numbers` - add line number to every line of code or not.
+pub fn format_code(
+    code: &str,
+    label: Option<&str>,
+    style: CodeStyle,
+    line_numbers: bool,
+) -> String {
+    if style == CodeStyle::Plain && !line_numbers {
+        return code.to_string();
+    }
+
+    let body: &str = code.trim_end_matches('\n');
+    let lines: Vec<&str> = body.split('\n').collect();
+    let number_width: usize = lines.len().to_string().len();
+    let prefix: &str = match style {
+        CodeStyle::Plain => "",
+        CodeStyle::Indent => CODE_INDENT,
+        CodeStyle::Box => "│ ",
+    };
+
+    let mut formatted: String = match (style, label) {
+        (CodeStyle::Plain, _) => String::new(),
+        (CodeStyle::Indent, Some(label)) => format!("{}[{}]\n", CODE_INDENT, label),
+        (CodeStyle::Indent, None) => String::new(),
+        (CodeStyle::Box, Some(label)) => format!("╭─ {}\n", label),
+        (CodeStyle::Box, None) => String::from("╭─\n"),
+    };
+    for (index, line) in lines.iter().enumerate() {
+        formatted.push_str(prefix);
+        if line_numbers {
+            formatted.push_str(&format!("{:>width$} │ ", index + 1, width = number_width));
+        }
+        formatted.push_str(line);
+        formatted.push('\n');
+    }
+    if style == CodeStyle::Box {
+        formatted.push_str("╰─\n");
+    }
+    if !code.ends_with('\n') {
+        formatted.pop();
+    }
+    formatted
+}
+
+/// Get the width of text when it's displayed in terminal.
+///
+/// ANSI escape sequences (like colors and hyperlinks) are not counted.
+pub fn display_width(text: &str) -> usize {
+    visible_chars(text).map(|c| c.width().unwrap_or(0)).sum()
+}
+
+/// Remove ANSI escape sequences (like colors and hyperlinks) from text.
+pub fn strip_escapes(text: &str) -> String {
+    visible_chars(text).collect()
+}
+
+/// Iterate over characters of text which are visible in terminal, ANSI escape sequences
+/// are skipped.
+fn visible_chars(text: &str) -> impl Iterator<Item = char> + '_ {
+    let mut chars = text.chars().peekable();
+    std::iter::from_fn(move || loop {
+        let c = chars.next()?;
+        if c != '\x1b' {
+            return Some(c);
+        }
+        match chars.next() {
+            // CSI sequence, e.g: "\x1b[31m", ends with a character in range '@'..='~'.
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC sequence, e.g: "\x1b]8;;link\x1b\\", ends with BEL or ST.
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => continue,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(
+            wrap_text("aaa bbb ccc ddd", Some(7)),
+            String::from("aaa bbb\nccc ddd")
+        );
+    }
+
+    #[test]
+    fn test_wrap_text_keeps_line_breaks_and_indentation() {
+        assert_eq!(
+            wrap_text("short\n  aaa bbb ccc", Some(9)),
+            String::from("short\n  aaa bbb\n  ccc")
+        );
+    }
+
+    #[test]
+    fn test_wrap_text_when_word_is_too_long() {
+        assert_eq!(
+            wrap_text("see https://stackoverflow.com/questions", Some(10)),
+            String::from("see\nhttps://stackoverflow.com/questions")
+        );
+    }
+
+    #[test]
+    fn test_wrap_text_without_width() {
+        assert_eq!(wrap_text("aaa bbb ccc", None), String::from("aaa bbb ccc"));
+    }
+
+    #[test]
+    fn test_format_code_plain() {
+        assert_eq!(
+            format_code("a = 1\n", Some("Python"), CodeStyle::Plain, false),
+            String::from("a = 1\n")
+        );
+    }
+
+    #[test]
+    fn test_format_code_indent_with_label() {
+        assert_eq!(
+            format_code("a = 1\nb = 2\n", Some("Python"), CodeStyle::Indent, false),
+            String::from("    [Python]\n    a = 1\n    b = 2\n")
+        );
+    }
+
+    #[test]
+    fn test_format_code_box_with_line_numbers() {
+        assert_eq!(
+            format_code("a = 1\nb = 2", None, CodeStyle::Box, true),
+            String::from("╭─\n│ 1 │ a = 1\n│ 2 │ b = 2\n╰─")
+        );
+    }
+
+    #[test]
+    fn test_display_width_ignores_escape_sequences() {
+        assert_eq!(display_width("\x1b[31mred\x1b[0m"), 3);
+        assert_eq!(
+            display_width("\x1b]8;;https://a.com\x1b\\link\x1b]8;;\x1b\\"),
+            4
+        );
+    }
+
+    #[test]
+    fn test_strip_escapes() {
+        assert_eq!(
+            strip_escapes("\x1b[31mred\x1b[0m \x1b]8;;https://a.com\x1b\\link\x1b]8;;\x1b\\"),
+            "red link"
+        );
+    }
+}
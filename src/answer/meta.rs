@@ -0,0 +1,272 @@
+//! Metadata of answer, which helps users to judge if the answer is trustworthy.
+
+use ansi_term::Color;
+use reqwest::Url;
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Attr, Class, Name, Predicate};
+use serde::Serialize;
+
+/// Answer metadata which is extracted from stackoverflow page.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct AnswerMeta {
+    /// the title of question.
+    pub question_title: Option<String>,
+    /// vote score of the answer.
+    pub score: Option<i32>,
+    /// indicate that the answer is accepted by the questioner or not.
+    pub accepted: bool,
+    /// display name of the answer author.
+    pub author: Option<String>,
+    /// link to the profile of answer author.
+    pub author_link: Option<String>,
+    /// when the answer is created, formatted as `yyyy-mm-dd`.
+    pub created: Option<String>,
+    /// when the answer is edited lastly, formatted as `yyyy-mm-dd`.
+    pub last_edited: Option<String>,
+}
+
+impl AnswerMeta {
+    /// Extract answer metadata from the page.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - the stackoverflow question page.
+    /// * `answer_node` - the answer we want to get metadata.
+    /// * `base_url` - the url to resolve relative links, like author profile.
+    pub fn parse(doc: &Document, answer_node: &Node, base_url: Option<&Url>) -> AnswerMeta {
+        let author_node: Option<Node> = author_node(answer_node);
+        AnswerMeta {
+            question_title: question_title(doc),
+            score: answer_score(answer_node),
+            accepted: is_accepted(answer_node),
+            author: author_node
+                .map(|node| node.text().trim().to_string())
+                .filter(|author| !author.is_empty()),
+            author_link: author_node
+                .and_then(|node| node.attr("href"))
+                .and_then(|href| base_url?.join(href).ok())
+                .map(String::from),
+            created: created_date(answer_node),
+            last_edited: last_edited_date(answer_node),
+        }
+    }
+
+    /// Render metadata as header lines of answer.
+    ///
+    /// # Returns
+    ///
+    /// Lines which contain question title and answer information, every line ends with
+    /// `\n`.  Returns an empty string if there is no metadata available.
+    pub fn render(&self, colorize: bool) -> String {
+        let paint = |color: Color, text: String| -> String {
+            if colorize {
+                color.paint(text).to_string()
+            } else {
+                text
+            }
+        };
+
+        let mut rendered: String = String::new();
+        if let Some(title) = &self.question_title {
+            rendered.push_str(&format!("  Q: {}\n", paint(Color::Cyan, title.clone())));
+        }
+
+        let mut fields: Vec<String> = Vec::new();
+        if let Some(score) = self.score {
+            let color = if score > 0 { Color::Green } else { Color::Red };
+            fields.push(paint(color, format!("▲ {}", score)));
+        }
+        if self.accepted {
+            fields.push(paint(Color::Green, String::from("✔ accepted")));
+        }
+        if let Some(author) = &self.author {
+            fields.push(format!("by {}", author));
+        }
+        if let Some(created) = &self.created {
+            fields.push(format!("answered {}", created));
+        }
+        if let Some(last_edited) = &self.last_edited {
+            fields.push(format!("edited {}", last_edited));
+        }
+        if !fields.is_empty() {
+            rendered.push_str(&format!("  {}\n", fields.join(" · ")));
+        }
+        rendered
+    }
+}
+
+/// Get question title from the question header, rather than guessing it from question link.
+fn question_title(doc: &Document) -> Option<String> {
+    doc.find(Attr("id", "question-header").descendant(Name("h1")))
+        .next()
+        .map(|node| node.text())
+        .or_else(|| {
+            // fallback to page title, which looks like `question title - Stack Overflow`.
+            doc.find(Name("title")).next().map(|node| {
+                let title = node.text();
+                match title.rfind(" - ") {
+                    Some(index) => title[..index].to_string(),
+                    None => title,
+                }
+            })
+        })
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+}
+
+/// Get answer score from `data-score` attribute, or vote count node.
+fn answer_score(answer_node: &Node) -> Option<i32> {
+    answer_node
+        .attr("data-score")
+        .map(String::from)
+        .or_else(|| {
+            answer_node
+                .find(Class("js-vote-count"))
+                .next()
+                .map(|node| node.text())
+        })
+        .and_then(|score| score.trim().parse().ok())
+}
+
+fn is_accepted(answer_node: &Node) -> bool {
+    answer_node.is(Class("accepted-answer"))
+        || answer_node.attr("itemprop") == Some("acceptedAnswer")
+}
+
+/// Get author node from the signature of answer owner.
+fn author_node<'a>(answer_node: &Node<'a>) -> Option<Node<'a>> {
+    let owner_signature = answer_node
+        .find(Class("post-signature").and(Class("owner")))
+        .next()
+        .or_else(|| answer_node.find(Class("post-signature")).last())?;
+    owner_signature
+        .find(Class("user-details").descendant(Name("a")))
+        .next()
+        .or_else(|| owner_signature.find(Class("user-details")).next())
+}
+
+fn created_date(answer_node: &Node) -> Option<String> {
+    answer_node
+        .find(Name("time").and(Attr("itemprop", "dateCreated")))
+        .next()
+        .and_then(|node| node.attr("datetime"))
+        .or_else(|| action_time(answer_node, "answered"))
+        .map(format_date)
+}
+
+fn last_edited_date(answer_node: &Node) -> Option<String> {
+    action_time(answer_node, "edited").map(format_date)
+}
+
+/// Get the time of given action, like `answered`, `edited` from answer signatures.
+///
+/// The signature looks like:
+/// `<div class="user-action-time">edited <span title="2018-07-23 10:42:10Z" class="relativetime">Jul 23, 2018</span></div>`
+fn action_time<'a>(answer_node: &Node<'a>, action: &str) -> Option<&'a str> {
+    answer_node
+        .find(Class("user-action-time"))
+        .filter(|node| node.text().trim_start().starts_with(action))
+        .filter_map(|node| node.find(Class("relativetime")).next())
+        .filter_map(|node| node.attr("title"))
+        .next()
+}
+
+/// Take date part from datetime, e.g: "2011-10-14 18:52:25Z" will become "2011-10-14".
+fn format_date(datetime: &str) -> String {
+    datetime.trim().chars().take(10).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_url() -> Url {
+        Url::parse("https://stackoverflow.com").unwrap()
+    }
+
+    const PAGE: &str = r#"
+<html>
+    <head><title>How to parse data in JSON format? - Stack Overflow</title></head>
+    <body>
+        <div id="question-header">
+            <h1 itemprop="name"><a href="/questions/7771011/how-to-parse-data-in-json-format">How to parse data in JSON format?</a></h1>
+        </div>
+        <div id="answer-7771053" class="answer accepted-answer" data-answerid="7771053" data-score="716">
+            <div class="js-vote-count">716</div>
+            <div class="js-post-body"><p>Very simple</p></div>
+            <time itemprop="dateCreated" datetime="2011-10-14T18:52:25"></time>
+            <div class="post-signature">
+                <div class="user-info">
+                    <div class="user-action-time">
+                        <a href="/posts/7771053/revisions">edited <span title="2018-07-23 10:42:10Z" class="relativetime">Jul 23, 2018</span></a>
+                    </div>
+                    <div class="user-details"><a href="/users/1">Editor</a></div>
+                </div>
+            </div>
+            <div class="post-signature owner">
+                <div class="user-info">
+                    <div class="user-action-time">
+                        answered <span title="2011-10-14 18:52:25Z" class="relativetime">Oct 14, 2011</span>
+                    </div>
+                    <div class="user-details" itemprop="author"><a href="/users/2">John Doe</a></div>
+                </div>
+            </div>
+        </div>
+    </body>
+</html>"#;
+
+    #[test]
+    fn test_parse_answer_meta() {
+        let doc: Document = Document::from(PAGE);
+        let answer: Node = doc.find(Class("answer")).next().unwrap();
+        assert_eq!(
+            AnswerMeta::parse(&doc, &answer, Some(&base_url())),
+            AnswerMeta {
+                question_title: Some(String::from("How to parse data in JSON format?")),
+                score: Some(716),
+                accepted: true,
+                author: Some(String::from("John Doe")),
+                author_link: Some(String::from("https://stackoverflow.com/users/2")),
+                created: Some(String::from("2011-10-14")),
+                last_edited: Some(String::from("2018-07-23")),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_answer_meta_when_information_is_missing() {
+        let doc: Document =
+            Document::from(r#"<div class="answer"><div class="js-vote-count">-3</div></div>"#);
+        let answer: Node = doc.find(Class("answer")).next().unwrap();
+        assert_eq!(
+            AnswerMeta::parse(&doc, &answer, Some(&base_url())),
+            AnswerMeta {
+                score: Some(-3),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_render_answer_meta() {
+        let meta = AnswerMeta {
+            question_title: Some(String::from("How to parse data in JSON format?")),
+            score: Some(716),
+            accepted: true,
+            author: Some(String::from("John Doe")),
+            author_link: None,
+            created: Some(String::from("2011-10-14")),
+            last_edited: None,
+        };
+        assert_eq!(
+            meta.render(false),
+            "  Q: How to parse data in JSON format?\n  ▲ 716 · ✔ accepted · by John Doe · answered 2011-10-14\n"
+        );
+    }
+
+    #[test]
+    fn test_render_empty_answer_meta() {
+        assert_eq!(AnswerMeta::default().render(false), "");
+    }
+}
@@ -1,6 +1,26 @@
+mod clipboard;
 mod colorize;
 mod crawler;
+mod export;
+mod history;
+mod hyperlink;
+mod last_query;
+mod layout;
+mod meta;
+mod post;
 mod precious;
 mod records;
-pub use precious::{get_answers, get_answers_with_client, SPLITTER};
-pub use records::clear_local_cache;
+mod snippet;
+mod stash;
+pub use history::{History, HistoryEntry, HistoryRef, Outcome};
+pub use last_query::LastQuery;
+pub use precious::{
+    collect_answers, get_answers, get_answers_with_cache, get_answers_with_client,
+    get_question_answers, Answer, Answers, SPLITTER,
+};
+pub use records::{clear_local_cache, PageCache};
+pub use stash::{Stash, StashedAnswer};
+
+pub(crate) use clipboard::copy_to_clipboard;
+pub(crate) use hyperlink::is_hyperlink_terminal;
+pub(crate) use precious::extract_question;
@@ -0,0 +1,203 @@
+//! Extract question body and comments of answer from stackoverflow page.
+
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Attr, Class, Name, Predicate};
+use serde::Serialize;
+
+/// A comment under the answer.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Comment {
+    /// display name of the comment author.
+    pub author: Option<String>,
+    /// when the comment is created, formatted as `yyyy-mm-dd`.
+    pub date: Option<String>,
+    /// vote score of the comment.
+    pub score: Option<i32>,
+    /// comment text.
+    pub text: String,
+}
+
+/// Get the body node of the given post (question or answer).
+///
+/// stackoverflow may return post body with `js-post-body` or `post-text` class.
+pub fn post_body<'a>(post_node: &Node<'a>) -> Option<Node<'a>> {
+    post_node
+        .find(Class("js-post-body"))
+        .next()
+        .or_else(|| post_node.find(Class("post-text")).next())
+}
+
+/// Get the question node of the page.
+pub fn question_node(doc: &Document) -> Option<Node<'_>> {
+    doc.find(Attr("id", "question"))
+        .next()
+        .or_else(|| doc.find(Class("question")).next())
+}
+
+/// Get the comment nodes under the given answer.
+///
+/// Note that stackoverflow only puts top comments in page, the rest of them are loaded
+/// dynamically, so they're not included.
+pub fn comment_nodes<'a>(answer_node: &Node<'a>) -> Vec<Node<'a>> {
+    answer_node
+        .find(Name("li").and(Class("comment")))
+        .filter(|node| node.find(Class("comment-copy")).next().is_some())
+        .collect()
+}
+
+/// Get the text node of comment, which may contain inline code and links.
+pub fn comment_copy<'a>(comment_node: &Node<'a>) -> Option<Node<'a>> {
+    comment_node.find(Class("comment-copy")).next()
+}
+
+/// Get links of the original questions if the question is closed as duplicate.
+///
+/// The duplicate notice looks like:
+/// `<aside class="js-post-notice"><b>This question already has answers here</b>:
+/// <ul><li><a href="/questions/123/title">title</a> (5 answers)</li></ul></aside>`,
+/// older pages use `question-originals-of-duplicate` class instead.
+pub fn duplicate_links(doc: &Document) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    let notices = doc
+        .find(Class("js-post-notice").or(Class("question-originals-of-duplicate")))
+        .filter(|notice| notice.text().contains("already has"));
+    for notice in notices {
+        let hrefs = notice
+            .find(Name("a"))
+            .filter_map(|node| node.attr("href"))
+            .filter(|href| href.contains("/questions/"));
+        for href in hrefs {
+            if !links.iter().any(|link| link == href) {
+                links.push(href.to_string());
+            }
+        }
+    }
+    links
+}
+
+impl Comment {
+    /// Extract comment information from the given comment node.
+    ///
+    /// A comment node looks like:
+    /// `<li class="comment"><div class="comment-score"><span>12</span></div>
+    /// <span class="comment-copy">comment text</span> – <a class="comment-user">author</a>
+    /// <span class="comment-date"><span title="2012-01-05 10:11:12Z">Jan 5, 2012</span></span></li>`
+    pub fn parse(comment_node: &Node) -> Comment {
+        let text: String = comment_copy(comment_node)
+            .map(|node| node.text().trim().to_string())
+            .unwrap_or_default();
+        let author: Option<String> = comment_node
+            .find(Class("comment-user"))
+            .next()
+            .map(|node| node.text().trim().to_string())
+            .filter(|author| !author.is_empty());
+        let date: Option<String> = comment_node
+            .find(Class("comment-date").descendant(Attr("title", ())))
+            .next()
+            .and_then(|node| node.attr("title"))
+            .map(|datetime| datetime.trim().chars().take(10).collect());
+        let score: Option<i32> = comment_node
+            .find(Class("comment-score"))
+            .next()
+            .and_then(|node| node.text().trim().parse().ok());
+        Comment {
+            author,
+            date,
+            score,
+            text,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"
+<html>
+    <body>
+        <div id="question" class="question">
+            <div class="js-post-body"><p>How to parse json?</p></div>
+        </div>
+        <div class="answer">
+            <div class="js-post-body"><p>Use json module</p></div>
+            <ul class="comments-list">
+                <li class="comment">
+                    <div class="comment-score"><span>12</span></div>
+                    <div class="comment-text">
+                        <span class="comment-copy">deprecated since <code>v2</code></span>
+                        – <a class="comment-user">John</a>
+                        <span class="comment-date"><span title="2012-01-05 10:11:12Z">Jan 5, 2012</span></span>
+                    </div>
+                </li>
+                <li class="comment">
+                    <div class="comment-text">
+                        <span class="comment-copy">thanks</span>
+                    </div>
+                </li>
+            </ul>
+        </div>
+    </body>
+</html>"#;
+
+    #[test]
+    fn test_question_body() {
+        let doc: Document = Document::from(PAGE);
+        let question: Node = question_node(&doc).unwrap();
+        assert_eq!(
+            post_body(&question).unwrap().text().trim(),
+            "How to parse json?"
+        );
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let doc: Document = Document::from(PAGE);
+        let answer: Node = doc.find(Class("answer")).next().unwrap();
+        let comments: Vec<Comment> = comment_nodes(&answer).iter().map(Comment::parse).collect();
+        assert_eq!(
+            comments,
+            vec![
+                Comment {
+                    author: Some(String::from("John")),
+                    date: Some(String::from("2012-01-05")),
+                    score: Some(12),
+                    text: String::from("deprecated since v2"),
+                },
+                Comment {
+                    author: None,
+                    date: None,
+                    score: None,
+                    text: String::from("thanks"),
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_links() {
+        let page = r#"
+<div id="question" class="question">
+    <aside class="s-notice post-notice js-post-notice">
+        <div><b>This question already has answers here</b>:</div>
+        <ul>
+            <li><a href="/questions/1/parse-json">Parse json</a> (5 answers)</li>
+            <li><a href="https://stackoverflow.com/questions/2/read-json">Read json</a></li>
+        </ul>
+        <a href="/help/duplicates">What does it mean?</a>
+    </aside>
+    <aside class="s-notice post-notice js-post-notice">
+        <b>Closed.</b> This question needs <a href="/questions/3/details">details</a>.
+    </aside>
+</div>"#;
+        assert_eq!(
+            duplicate_links(&Document::from(page)),
+            vec![
+                String::from("/questions/1/parse-json"),
+                String::from("https://stackoverflow.com/questions/2/read-json"),
+            ]
+        );
+        assert!(duplicate_links(&Document::from(PAGE)).is_empty());
+    }
+}
@@ -1,19 +1,113 @@
 //! This module contains api to get results from stack overflow page.
 //! Yeah, our precious lays in stackoverflow.com.
 
-use super::colorize::colorize_code;
-use super::crawler::{CrawlerMsg, PageCrawler};
-use crate::config::{Config, OutputOption};
-use crate::error::Result;
+use super::clipboard::copy_to_clipboard;
+use super::colorize::{colorize_code, syntax_name};
+use super::crawler::{fetch_page, linked_answer_id, CrawledData, PageCrawler};
+use super::export::{export_answer, export_document, Attribution, Block, Inline};
+use super::hyperlink::hyperlink;
+use super::layout::{format_code, wrap_text};
+use super::meta::AnswerMeta;
+use super::post::{
+    comment_copy, comment_nodes, duplicate_links, post_body, question_node, Comment,
+};
+use super::records::PageCache;
+use super::snippet::{best_code_index, clean_code, code_blocks};
+use crate::config::{CodeStyle, Config, Date, ExportFormat, OutputOption, SearchFilter};
+use crate::engine::SearchHit;
+use crate::error::{Error, Result};
+use crate::link::QuestionLink;
 use reqwest::{Client, ClientBuilder, Url};
 use select::document::Document;
 use select::node::Node;
-use select::predicate::{Class, Name};
+use select::predicate::{Attr, Class, Name, Predicate};
+use serde::Serialize;
 use std::collections::HashSet;
-use tokio::sync::mpsc::{self, Receiver, Sender};
 
 pub const SPLITTER: &str = "\n^_^ ==================================================== ^_^\n\n";
 
+/// Default site to resolve relative links in answer.
+const DEFAULT_SITE: &str = "https://stackoverflow.com";
+/// How many pages can be skipped at most when there are no answers in them.
+const MAX_SKIPPED_PAGES: usize = 5;
+
+/// Answer which is parsed from stackoverflow page.
+#[derive(Debug)]
+struct ParsedAnswer {
+    /// id of the answer, which can be used to make answer permalink.
+    answer_id: Option<String>,
+    /// answer metadata, like score, author.
+    meta: AnswerMeta,
+    /// formatted answer content, which can be output directly.
+    content: String,
+    /// formatted question body, it's available when user wants to see question.
+    question: Option<String>,
+    /// formatted comments of answer, it's available when user wants to see comments.
+    comments: Option<String>,
+    /// plain text of answer, which is used by structured output.
+    text: AnswerText,
+    /// answer blocks, it's available when answers are exported as notes.
+    blocks: Vec<Block>,
+}
+
+/// Plain text of answer and it's relative question, which is used by structured output.
+#[derive(Debug, Default, Serialize)]
+struct AnswerText {
+    /// question tags.
+    tags: Vec<String>,
+    /// question body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    question: Option<String>,
+    /// answer body.
+    body: String,
+    /// code in answer.
+    code: Option<String>,
+    /// all code blocks in answer, it's given only if user wants all of them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    codes: Option<Vec<String>>,
+    /// comments under the answer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comments: Option<Vec<Comment>>,
+}
+
+/// An answer of question, which can be browsed one by one.
+#[derive(Debug, Clone)]
+pub struct Answer {
+    /// rendered answer, which can be output to terminal directly.
+    pub content: String,
+    /// code snippet in answer, it's not colorized.
+    pub code: Option<String>,
+    /// answer permalink.
+    pub permalink: String,
+    /// vote score of answer.
+    pub score: Option<i32>,
+    /// title of question.
+    pub question_title: Option<String>,
+}
+
+/// Answer representation of structured(json) output.
+#[derive(Serialize)]
+struct JsonAnswer<'a> {
+    link: &'a str,
+    permalink: String,
+    /// the question which is closed as duplicate of `link`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redirected_from: Option<&'a str>,
+    #[serde(flatten)]
+    meta: &'a AnswerMeta,
+    #[serde(flatten)]
+    text: &'a AnswerText,
+}
+
+/// Link representation of structured(json) output.
+#[derive(Serialize)]
+struct JsonLink<'a> {
+    title: String,
+    link: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<&'a str>,
+}
+
 /// Get answers from given links.
 ///
 /// This function will go through network to find out answers.
@@ -81,40 +175,515 @@ pub async fn get_answers_with_client(
     conf: Config,
     client: Client,
 ) -> Result<String> {
-    let results: Result<String> = match conf.option() {
-        OutputOption::Links => Ok(answers_links_only(links, conf.numbers() as usize)),
-        _ => get_detailed_answer(links, conf, client).await,
+    get_answers_with_cache(links, conf, client, &PageCache::load()).await
+}
+
+/// Get answers from given links, pages are taken from the given cache if possible.
+///
+/// It's useful when answers are fetched many times in one process, so local cache
+/// file is loaded only once.
+///
+/// # Examples
+///
+/// ```rust
+/// use hors::{answer, Config, OutputOption, PageCache};
+/// use reqwest::{Client, ClientBuilder};
+///
+/// # async fn run() {
+/// let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
+/// let client: Client = ClientBuilder::new().cookie_store(true).build().unwrap();
+/// let page_cache: PageCache = PageCache::load();
+/// let links: Vec<String> = vec![
+///     String::from("https://stackoverflow.com/questions/7771011/how-to-parse-data-in-json")
+/// ];
+/// for _ in 0..2 {
+///     let answers: String =
+///         hors::get_answers_with_cache(&links, conf.clone(), client.clone(), &page_cache)
+///             .await
+///             .unwrap();
+///     assert!(answers.contains("json.loads"));
+/// }
+/// # }
+/// ```
+pub async fn get_answers_with_cache(
+    links: &[String],
+    conf: Config,
+    client: Client,
+    page_cache: &PageCache,
+) -> Result<String> {
+    let answers: Answers = collect_answers(links, conf, client, page_cache).await?;
+    Ok(answers.output)
+}
+
+/// Answers output, along with links which are used to get them.
+#[derive(Debug)]
+pub struct Answers {
+    /// the result string which can be print to terminal directly.
+    pub output: String,
+    /// links which answers come from, they're in the order of given links.
+    pub answered_links: Vec<String>,
+    /// how many given links are consumed, including links which are skipped because
+    /// they're not questions, fail to fetch or have no acceptable answer.
+    pub consumed: usize,
+}
+
+/// Get answers from given links, like `get_answers_with_cache`, and tell which links
+/// are used.
+///
+/// Links may be skipped to get enough answers, so it's useful to know where to continue
+/// for the following answers.
+///
+/// # Examples
+///
+/// ```rust
+/// use hors::{self, Answers, Config, OutputOption, PageCache};
+/// use reqwest::{Client, ClientBuilder};
+///
+/// # async fn run() {
+/// let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
+/// let client: Client = ClientBuilder::new().cookie_store(true).build().unwrap();
+/// let links: Vec<String> = vec![
+///     String::from("https://stackoverflow.com/tags/json"),
+///     String::from("https://stackoverflow.com/questions/7771011/how-to-parse-data-in-json"),
+/// ];
+/// let answers: Answers = hors::collect_answers(&links, conf, client, &PageCache::load())
+///     .await
+///     .unwrap();
+/// assert_eq!(answers.answered_links, vec![links[1].clone()]);
+/// assert_eq!(answers.consumed, 2);
+/// # }
+/// ```
+pub async fn collect_answers(
+    links: &[String],
+    conf: Config,
+    client: Client,
+    page_cache: &PageCache,
+) -> Result<Answers> {
+    let numbers: usize = conf.numbers() as usize;
+    let output: String = match conf.option() {
+        OutputOption::Links if conf.json() => {
+            answers_links_json(links, conf.search_hits(), numbers)?
+        }
+        OutputOption::Links => answers_links_only(
+            links,
+            conf.search_hits(),
+            numbers,
+            &conf.splitter(),
+            conf.hyperlink(),
+        ),
+        _ => return get_detailed_answer(links, conf, client, page_cache.clone()).await,
     };
+    let (answered_links, consumed) = take_question_links(links, numbers);
+    Ok(Answers {
+        output,
+        answered_links: answered_links.into_iter().cloned().collect(),
+        consumed,
+    })
+}
 
-    results
+/// Get all answers of the given question link, answers are ordered by vote score.
+///
+/// Unlike `get_answers`, it fetches only one page, so it's suitable to browse answers of
+/// a question one by one.
+///
+/// # Examples
+///
+/// ```rust
+/// use hors::{self, Answer, Config, OutputOption, PageCache};
+/// use reqwest::{Client, ClientBuilder};
+///
+/// # async fn run() {
+/// let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
+/// let client: Client = ClientBuilder::new().cookie_store(true).build().unwrap();
+/// let answers: Vec<Answer> = hors::get_question_answers(
+///     "https://stackoverflow.com/questions/7771011/how-to-parse-data-in-json",
+///     &conf,
+///     &client,
+///     &PageCache::load(),
+/// )
+/// .await
+/// .unwrap();
+/// assert!(answers.len() > 1);
+/// # }
+/// ```
+pub async fn get_question_answers(
+    link: &str,
+    conf: &Config,
+    client: &Client,
+    page_cache: &PageCache,
+) -> Result<Vec<Answer>> {
+    let page: String = fetch_page(link, client, page_cache).await?;
+    let mut answers: Vec<ParsedAnswer> = parse_all_answers(&page, conf);
+    // the answer which link points to comes first.
+    if let Some(linked_id) = linked_answer_id(link) {
+        answers.sort_by_key(|answer| answer.answer_id.as_deref() != Some(linked_id.as_str()));
+    }
+    let mut question_link: String = link.to_string();
+    let mut redirected_from: Option<&str> = None;
+    if answers.is_empty() {
+        let parse = |page: &str, conf: &Config| {
+            Some(parse_all_answers(page, conf)).filter(|answers| !answers.is_empty())
+        };
+        if let Some((original, original_answers)) =
+            follow_duplicate(link, &page, conf, client, page_cache, parse).await
+        {
+            question_link = original;
+            answers = original_answers;
+            redirected_from = Some(link);
+        }
+    }
+    let answers: Vec<Answer> = answers
+        .into_iter()
+        .map(|answer| Answer {
+            code: answer.text.code.clone(),
+            permalink: answer_permalink(&question_link, answer.answer_id.as_deref()),
+            score: answer.meta.score,
+            question_title: answer.meta.question_title.clone(),
+            content: render_answer(answer, &question_link, redirected_from, conf),
+        })
+        .collect();
+    Ok(answers)
 }
 
-async fn get_detailed_answer(links: &[String], conf: Config, client: Client) -> Result<String> {
+async fn get_detailed_answer(
+    links: &[String],
+    conf: Config,
+    client: Client,
+    page_cache: PageCache,
+) -> Result<Answers> {
     let mut results: Vec<String> = Vec::new();
+    let mut json_results: Vec<serde_json::Value> = Vec::new();
+    let mut snippets: Vec<String> = Vec::new();
+
+    // links which are not questions, pages which fail to fetch or have no acceptable
+    // answer are skipped, so keep crawling until we get enough answers.
+    let numbers: usize = conf.numbers() as usize;
+    let mut page_crawler = PageCrawler::new(
+        links.into(),
+        client.clone(),
+        page_cache.clone(),
+        numbers + MAX_SKIPPED_PAGES,
+    );
+    let mut missed_links: Vec<String> = Vec::new();
+    let mut answered_links: Vec<String> = Vec::new();
+    while answered_links.len() < numbers {
+        let pages: Vec<CrawledData> = page_crawler.fetch(numbers - answered_links.len()).await;
+        if pages.is_empty() {
+            break;
+        }
+        for m in pages {
+            let (link, redirected_from, answer) =
+                match resolve_answer(&m, &conf, &client, &page_cache).await {
+                    Some(resolved) => resolved,
+                    None if conf.filter().is_empty() => {
+                        warn!("Can't get answer from {}", m.get_link());
+                        missed_links.push(m.get_link().to_string());
+                        continue;
+                    }
+                    None => {
+                        debug!("Skip {} which doesn't match filters", m.get_link());
+                        continue;
+                    }
+                };
+            // record the searched link, rather than the original of duplicate question.
+            answered_links.push(m.get_link().to_string());
+            let link: &str = &link;
+            if let Some(code) = answer.text.code.as_ref() {
+                snippets.push(code.clone());
+            }
+            if conf.json() {
+                let json_answer = JsonAnswer {
+                    link,
+                    permalink: answer_permalink(link, answer.answer_id.as_deref()),
+                    redirected_from,
+                    meta: &answer.meta,
+                    text: &answer.text,
+                };
+                json_results.push(
+                    serde_json::to_value(json_answer)
+                        .map_err(|_| Error::from_parse("Serialize answer failed"))?,
+                );
+            } else if let Some(format) = conf.export() {
+                results.push(export_parsed_answer(&answer, link, redirected_from, format));
+            } else {
+                results.push(render_answer(answer, link, redirected_from, &conf));
+            }
+        }
+    }
+    // tell user why nothing is shown.
+    if answered_links.is_empty() && !conf.json() && conf.export().is_none() {
+        results.extend(
+            missed_links
+                .iter()
+                .map(|link| format!("Can't get answer from {}", link)),
+        );
+    }
 
-    let (tx, mut rx): (Sender<CrawlerMsg>, Receiver<CrawlerMsg>) = mpsc::channel(10);
+    if conf.copy() {
+        copy_snippets(&snippets);
+    }
+    let output: String = if conf.json() {
+        serde_json::to_string_pretty(&json_results)
+            .map_err(|_| Error::from_parse("Serialize answers failed"))?
+    } else if let Some(format) = conf.export() {
+        export_document(format, &results)
+    } else {
+        results.join(&conf.splitter())
+    };
+    Ok(Answers {
+        output,
+        answered_links,
+        consumed: page_crawler.consumed(),
+    })
+}
 
-    let page_crawler = PageCrawler::new(links.into(), conf, client, tx);
-    page_crawler.fetch();
+/// Parse the answer which the crawled link points to.
+///
+/// Questions which are closed as duplicate are redirected to the original, filters are
+/// checked against the page which the answer comes from.
+///
+/// # Returns
+///
+/// Link of the question which the answer comes from, the duplicate question which is
+/// redirected from, and the answer.  None if no answer matches filters.
+async fn resolve_answer<'a>(
+    m: &'a CrawledData,
+    conf: &Config,
+    client: &Client,
+    page_cache: &PageCache,
+) -> Option<(String, Option<&'a str>, ParsedAnswer)> {
+    if let Some(answer) = parse_linked_answer(m.get_page(), m.get_answer_id(), conf) {
+        if !conf.filter().is_empty() && !page_matches_filter(m.get_page(), conf.filter()) {
+            return None;
+        }
+        return Some((m.get_link().to_string(), None, answer));
+    }
+    // questions which are closed as duplicate are redirected to the original.
+    let (original, answer) = follow_duplicate(
+        m.get_link(),
+        m.get_page(),
+        conf,
+        client,
+        page_cache,
+        parse_answer,
+    )
+    .await?;
+    Some((original, Some(m.get_link()), answer))
+}
 
-    while let Some(page) = rx.recv().await {
-        match page {
-            CrawlerMsg::Done => break,
-            CrawlerMsg::Data(m) => {
-                let answer: Option<String> = parse_answer(m.get_page().into(), &conf);
-                match answer {
-                    Some(content) => results.push(format!("{}\n{}", m.get_title(), content)),
-                    None => results.push(format!("Can't get answer from {}", m.get_link())),
-                }
+/// Render answer as text which can be output to terminal directly.
+///
+/// The answer is headed by link and metadata, question and comments are included if
+/// they're available.  `redirected_from` is the question which is closed as duplicate
+/// of `link`, it's noted in the header.
+fn render_answer(
+    answer: ParsedAnswer,
+    link: &str,
+    redirected_from: Option<&str>,
+    conf: &Config,
+) -> String {
+    let permalink: String = answer_permalink(link, answer.answer_id.as_deref());
+    let link: String = if conf.hyperlink() {
+        hyperlink(&permalink, link)
+    } else {
+        link.to_string()
+    };
+    let meta: String = if conf.meta() {
+        answer.meta.render(conf.colorize())
+    } else {
+        String::new()
+    };
+    let mut result: String = format!("- Answer from {}\n", link);
+    if let Some(duplicate) = redirected_from {
+        let duplicate: String = if conf.hyperlink() {
+            hyperlink(duplicate, duplicate)
+        } else {
+            duplicate.to_string()
+        };
+        result.push_str(&format!(
+            "- Redirected from {} (closed as duplicate)\n",
+            duplicate
+        ));
+    }
+    result.push_str(&meta);
+    if let Some(question) = answer.question {
+        result.push_str(&section_title("Question", conf.colorize()));
+        result.push_str(question.trim_end());
+        result.push_str("\n\n");
+        result.push_str(&section_title("Answer", conf.colorize()));
+    }
+    result.push_str(&answer.content);
+    if let Some(comments) = answer.comments {
+        result.push('\n');
+        result.push_str(&section_title("Comments", conf.colorize()));
+        result.push_str(&comments);
+    }
+    result
+}
+
+/// Export answer as note, along with the attribution required by CC BY-SA license.
+fn export_parsed_answer(
+    answer: &ParsedAnswer,
+    link: &str,
+    redirected_from: Option<&str>,
+    format: ExportFormat,
+) -> String {
+    let title: String = match &answer.meta.question_title {
+        Some(title) => title.clone(),
+        None => Url::parse(link)
+            .map(|url| extract_question(url.path()))
+            .unwrap_or_else(|_| link.to_string()),
+    };
+    let permalink: String = answer_permalink(link, answer.answer_id.as_deref());
+    let attribution = Attribution {
+        title: &title,
+        link,
+        permalink: &permalink,
+        author: answer.meta.author.as_deref(),
+        author_link: answer.meta.author_link.as_deref(),
+        date: answer
+            .meta
+            .last_edited
+            .as_deref()
+            .or(answer.meta.created.as_deref()),
+        redirected_from,
+    };
+    export_answer(format, &answer.blocks, &attribution)
+}
+
+/// Copy code snippets to clipboard, failure is not fatal because answers are output anyway.
+fn copy_snippets(snippets: &[String]) {
+    if snippets.is_empty() {
+        warn!("No code snippet to copy");
+        return;
+    }
+    let text: String = snippets
+        .iter()
+        .map(|snippet| snippet.trim_end())
+        .collect::<Vec<&str>>()
+        .join("\n\n");
+    if let Err(err) = copy_to_clipboard(&text) {
+        warn!("Copy code snippet to clipboard failed: {}", err);
+    }
+}
+
+/// Make title line of answer section, like question, comments.
+fn section_title(title: &str, colorize: bool) -> String {
+    if colorize {
+        format!(
+            "{}\n",
+            ansi_term::Style::new().bold().paint(format!("[{}]", title))
+        )
+    } else {
+        format!("[{}]\n", title)
+    }
+}
+
+/// Parse the answer which the link points to.
+///
+/// The answer is selected by votes if link doesn't point to an answer, or the answer is
+/// gone.
+fn parse_linked_answer(
+    page: &str,
+    linked_id: Option<&str>,
+    config: &Config,
+) -> Option<ParsedAnswer> {
+    if let Some(linked_id) = linked_id {
+        let doc: Document = Document::from(page);
+        let question_tags: Vec<String> = question_tags(&doc);
+        let linked: Option<ParsedAnswer> = doc
+            .find(Class("answer"))
+            .find(|node| answer_id(node).as_deref() == Some(linked_id))
+            .and_then(|node| parse_answer_node(&doc, node, &question_tags, config))
+            .filter(|answer| answer_matches(&answer.meta, config.filter()));
+        if linked.is_some() {
+            return linked;
+        }
+        debug!("Can't find answer {}, select answer by votes", linked_id);
+    }
+    parse_answer(page, config)
+}
+
+fn parse_answer(page: &str, config: &Config) -> Option<ParsedAnswer> {
+    let doc: Document = Document::from(page);
+    let question_tags: Vec<String> = question_tags(&doc);
+    let answer: Node = match select_answer(&doc, config.filter()) {
+        Ok(answer) => answer?,
+        Err(err) => {
+            warn!("Can't select answer, error msg: {:?}", err);
+            return None;
+        }
+    };
+    parse_answer_node(&doc, answer, &question_tags, config)
+}
+
+/// Parse all answers of the question page, answers are ordered by vote score.
+fn parse_all_answers(page: &str, config: &Config) -> Vec<ParsedAnswer> {
+    let doc: Document = Document::from(page);
+    let question_tags: Vec<String> = question_tags(&doc);
+    if !tags_match(&question_tags, config.filter()) {
+        return Vec::new();
+    }
+    let mut answers: Vec<ParsedAnswer> = doc
+        .find(Class("answer"))
+        .filter_map(|answer| parse_answer_node(&doc, answer, &question_tags, config))
+        .filter(|answer| answer_matches(&answer.meta, config.filter()))
+        .collect();
+    answers.sort_by_key(|answer| std::cmp::Reverse(answer.meta.score.unwrap_or(i32::MIN)));
+    answers
+}
+
+/// Follow the question which is closed as duplicate to its original questions.
+///
+/// Original questions are fetched through page cache, and parsed by `parse` one by one
+/// until one of them has answers.
+///
+/// # Returns
+///
+/// The original question link along with the parsed result, or None if the question
+/// is not a duplicate or no original question has answers.
+async fn follow_duplicate<T>(
+    link: &str,
+    page: &str,
+    conf: &Config,
+    client: &Client,
+    page_cache: &PageCache,
+    parse: impl Fn(&str, &Config) -> Option<T>,
+) -> Option<(String, T)> {
+    for original in original_links(page, link) {
+        debug!("{} is closed as duplicate of {}", link, original);
+        let original_page: String = match fetch_page(&original, client, page_cache).await {
+            Ok(page) => page,
+            Err(err) => {
+                warn!("Can't fetch {}, error msg: {:?}", original, err);
+                continue;
             }
+        };
+        if !conf.filter().is_empty() && !page_matches_filter(&original_page, conf.filter()) {
+            continue;
+        }
+        if let Some(parsed) = parse(&original_page, conf) {
+            return Some((original, parsed));
         }
     }
+    None
+}
 
-    Ok(results.join(SPLITTER))
+/// Get absolute links of original questions if the question page is a duplicate.
+fn original_links(page: &str, link: &str) -> Vec<String> {
+    let doc: Document = Document::from(page);
+    let base_url: Option<Url> = Url::parse(link).ok();
+    duplicate_links(&doc)
+        .iter()
+        .filter_map(|href| match &base_url {
+            Some(base_url) => base_url.join(href).ok().map(String::from),
+            None => Url::parse(href).ok().map(String::from),
+        })
+        .collect()
 }
 
-fn parse_answer(page: String, config: &Config) -> Option<String> {
-    let doc: Document = Document::from(page.as_str());
+/// Get question tags of the page, which are sorted by the popularity of language.
+fn question_tags(doc: &Document) -> Vec<String> {
     // The question tags may contains useful information about the language topic
     // so syntect can use correct Syntex reference.
     let mut question_tags: Vec<String> = doc
@@ -129,101 +698,527 @@ fn parse_answer(page: String, config: &Config) -> Option<String> {
     // So we make another way: sort these question tags by the 'Popularity of programming language'.
     // The language is more 'popular', the more possibility to get right syntax set.
     sort_tags(&mut question_tags);
+    question_tags
+}
 
-    let appropriate_answer = select_answer(&doc);
+fn parse_answer_node(
+    doc: &Document,
+    answer: Node,
+    question_tags: &[String],
+    config: &Config,
+) -> Option<ParsedAnswer> {
+    let tags: Vec<String> = question_tags.to_vec();
+    // the language of user's project is preferred to guess syntax of code.
+    let question_tags: &[String] = &syntax_tags(question_tags, config);
+    let site_url: Option<Url> = page_url(doc);
+    // links in answer are made clickable, relative links are resolved by the page url.
+    let base_url: Option<Url> = if config.hyperlink() {
+        site_url.clone()
+    } else {
+        None
+    };
+    let codes: Vec<Node> = select_code(doc, &answer, config);
+    let content: Option<String> = match *config.option() {
+        OutputOption::OnlyCode => parse_answer_instruction(&codes, question_tags, config),
+        OutputOption::All => {
+            parse_answer_detailed(answer, question_tags, config, base_url.as_ref())
+        }
+        _ => panic!(
+            "parse_answer shoudn't get config with OutputOption::Link.\n
+            If you get this message, please fire an issue"
+        ),
+    };
+    let content: String = content?;
 
-    if let Some(answer) = appropriate_answer {
-        match *config.option() {
-            OutputOption::OnlyCode => {
-                return parse_answer_instruction(answer, question_tags, config.colorize());
-            }
-            OutputOption::All => {
-                return parse_answer_detailed(answer, question_tags, config.colorize());
-            }
-            _ => panic!(
-                "parse_answer shoudn't get config with OutputOption::Link.\n
-                If you get this message, please fire an issue"
-            ),
+    let question_body: Option<Node> = if config.question() {
+        question_node(doc).and_then(|question| post_body(&question))
+    } else {
+        None
+    };
+    let comments: Option<Vec<Node>> = if config.comments() {
+        Some(comment_nodes(&answer))
+    } else {
+        None
+    };
+
+    let blocks: Vec<Block> = match (config.export(), config.option()) {
+        (None, _) => Vec::new(),
+        (Some(_), OutputOption::OnlyCode) => codes
+            .iter()
+            .map(|code| Block::Code {
+                language: code_language(code, question_tags),
+                code: code_text(code, config),
+            })
+            .collect(),
+        (Some(_), _) => post_body(&answer)
+            .map(|body| post_blocks(&body, question_tags, config, site_url.as_ref()))
+            .unwrap_or_default(),
+    };
+    let text = AnswerText {
+        tags,
+        question: question_body.map(|body| body.text().trim().to_string()),
+        body: post_body(&answer)
+            .map(|body| body.text().trim().to_string())
+            .unwrap_or_default(),
+        code: codes.first().map(|code| code_text(code, config)),
+        codes: if config.all_code() {
+            Some(codes.iter().map(|code| code_text(code, config)).collect())
+        } else {
+            None
+        },
+        comments: comments
+            .as_ref()
+            .map(|comments| comments.iter().map(Comment::parse).collect()),
+    };
+    Some(ParsedAnswer {
+        answer_id: answer_id(&answer),
+        meta: AnswerMeta::parse(doc, &answer, site_url.as_ref()),
+        content,
+        question: question_body
+            .map(|body| render_post(&body, question_tags, config, base_url.as_ref())),
+        comments: comments
+            .map(|comments| render_comments(&comments, question_tags, config, base_url.as_ref())),
+        text,
+        blocks,
+    })
+}
+
+/// Get the id of answer node.
+///
+/// The answer node looks like `<div id="answer-12345" class="answer" data-answerid="12345">`.
+fn answer_id(answer_node: &Node) -> Option<String> {
+    answer_node
+        .attr("data-answerid")
+        .or_else(|| {
+            answer_node
+                .attr("id")
+                .and_then(|id| id.strip_prefix("answer-"))
+        })
+        .map(String::from)
+}
+
+/// Make permalink of the answer which is under the question link.
+///
+/// # Returns
+///
+/// The answer permalink like `https://stackoverflow.com/a/12345`, or the question link
+/// if answer id is unknown.
+fn answer_permalink(question_link: &str, answer_id: Option<&str>) -> String {
+    match (Url::parse(question_link), answer_id) {
+        (Ok(mut url), Some(answer_id)) => {
+            url.set_path(&format!("/a/{}", answer_id));
+            url.set_query(None);
+            url.set_fragment(None);
+            url.into()
         }
+        _ => question_link.to_string(),
     }
-    None
+}
+
+/// Get url of the stackoverflow page, it's used to resolve relative links in answer.
+fn page_url(doc: &Document) -> Option<Url> {
+    let canonical: Option<&str> = doc
+        .find(Name("link").and(Attr("rel", "canonical")))
+        .next()
+        .and_then(|node| node.attr("href"));
+    canonical
+        .and_then(|link| Url::parse(link).ok())
+        .or_else(|| Url::parse(DEFAULT_SITE).ok())
 }
 
 /// Select answer by most voted.
-fn select_answer(doc: &Document) -> Option<Node> {
+///
+/// It fails if vote count of answer is missing or not a number, which means the page
+/// layout is changed.
+fn select_answer<'a>(doc: &'a Document, filter: &SearchFilter) -> Result<Option<Node<'a>>> {
     let mut selected_node: Option<Node> = None;
-    let mut selected_voted: i16 = 0;
+    // when filters are given, answers which are not voted can be selected too, because
+    // user asks for them explicitly (like the accepted answer of a new question).
+    let mut selected_voted: i16 = if filter.is_empty() { 0 } else { i16::MIN };
     let answers = doc.find(Class("answer"));
 
     for answer in answers {
+        if !filter.is_empty() && !answer_matches(&AnswerMeta::parse(doc, &answer, None), filter) {
+            continue;
+        }
         // fetch vote count to know which answer is best for users.
-        let voted: Node = answer.find(Class("js-vote-count")).next().expect(
-            "Can't find vote information :(  If you see this message, please fire an issue.",
-        );
+        let voted: Node = answer
+            .find(Class("js-vote-count"))
+            .next()
+            .ok_or_else(|| Error::from_parse("Can't find vote information of answer"))?;
         debug!("Voted node infromation {:?}", voted);
         // Hors think that the voted number should less than 32767, so make it i16 type.
         let voted: i16 = voted
             .text()
             .trim()
             .parse()
-            .expect("Vote information should be a number :(  If you see this message, please fire an issue.˝");
+            .map_err(|_| Error::from_parse("Vote information of answer should be a number"))?;
         if selected_voted < voted {
             selected_voted = voted;
             selected_node = Some(answer);
         }
     }
-    selected_node
+    Ok(selected_node)
+}
+
+/// Return true if the question page has tags and answers which match the filter.
+fn page_matches_filter(page: &str, filter: &SearchFilter) -> bool {
+    let doc: Document = Document::from(page);
+    tags_match(&question_tags(&doc), filter) && matches!(select_answer(&doc, filter), Ok(Some(_)))
+}
+
+/// Return true if the question has all tags of filter, case is ignored.
+fn tags_match(question_tags: &[String], filter: &SearchFilter) -> bool {
+    filter.tags.iter().all(|tag| {
+        question_tags
+            .iter()
+            .any(|question_tag| question_tag.eq_ignore_ascii_case(tag))
+    })
+}
+
+/// Return true if the answer matches score, date and accepted filters.
+fn answer_matches(meta: &AnswerMeta, filter: &SearchFilter) -> bool {
+    let created: Option<Date> = meta
+        .created
+        .as_deref()
+        .and_then(|created| created.parse().ok());
+    (!filter.accepted_only || meta.accepted)
+        && filter
+            .min_score
+            .is_none_or(|min_score| meta.score.is_some_and(|score| score >= min_score))
+        && filter
+            .since
+            .is_none_or(|since| created.is_some_and(|created| created >= since))
+        && filter
+            .until
+            .is_none_or(|until| created.is_some_and(|created| created <= until))
+}
+
+/// Select code blocks in answer which should be output.
+///
+/// By default only the most relevant code block is selected, user can choose a code block by
+/// it's index (starts from 1), or select all code blocks.
+fn select_code<'a>(doc: &Document, answer_node: &Node<'a>, config: &Config) -> Vec<Node<'a>> {
+    let blocks: Vec<Node> = code_blocks(answer_node);
+    if config.all_code() {
+        return blocks;
+    }
+    if let Some(index) = config.code_index() {
+        match blocks.get(index.wrapping_sub(1)) {
+            Some(block) => return vec![*block],
+            None => warn!(
+                "Code block {} doesn't exist, there are {} code blocks in answer",
+                index,
+                blocks.len()
+            ),
+        }
+    }
+
+    let question_blocks: Vec<String> = question_node(doc)
+        .map(|question| {
+            code_blocks(&question)
+                .iter()
+                .map(|node| node.text())
+                .collect()
+        })
+        .unwrap_or_default();
+    let block_texts: Vec<String> = blocks.iter().map(|node| node.text()).collect();
+    best_code_index(&block_texts, config.query(), &question_blocks)
+        .map(|index| vec![blocks[index]])
+        .unwrap_or_default()
+}
+
+/// Get text of the code node, it's cleaned up if user wants.
+fn code_text(code_node: &Node, config: &Config) -> String {
+    if config.clean() {
+        clean_code(&code_node.text())
+    } else {
+        code_node.text()
+    }
 }
 
 fn parse_answer_instruction(
-    answer_node: select::node::Node,
-    question_tags: Vec<String>,
-    should_colorize: bool,
+    codes: &[Node],
+    question_tags: &[String],
+    config: &Config,
 ) -> Option<String> {
-    let code_elements: [&str; 2] = ["pre", "code"];
-    for code_element in &code_elements {
-        if let Some(title) = answer_node.find(Name(*code_element)).next() {
-            if should_colorize {
-                return Some(colorize_code(title.text(), &question_tags));
-            } else {
-                return Some(title.text());
-            }
+    if codes.is_empty() {
+        return None;
+    }
+    let mut formatted_codes: Vec<String> = Vec::new();
+    for (index, code_node) in codes.iter().enumerate() {
+        let code: String = if config.colorize() {
+            colorize_code(
+                code_text(code_node, config),
+                &code_tags(code_node, question_tags),
+            )
+        } else {
+            code_text(code_node, config)
+        };
+        // only code is output, keep it as it is so user can paste it directly.
+        let code: String = format_code(&code, None, CodeStyle::Plain, config.line_numbers());
+        if config.all_code() {
+            let title: String = format!("Code {}", index + 1);
+            formatted_codes.push(format!(
+                "{}{}",
+                section_title(&title, config.colorize()),
+                code
+            ));
+        } else {
+            formatted_codes.push(code);
         }
     }
-    None
+    Some(formatted_codes.join("\n"))
 }
 
 fn parse_answer_detailed(
     answer_node: select::node::Node,
-    question_tags: Vec<String>,
-    should_colorize: bool,
+    question_tags: &[String],
+    config: &Config,
+    base_url: Option<&Url>,
 ) -> Option<String> {
-    // stackoverflow may return answer body with `js-post-body` or `post-text` class.
-    // so we should class decision first.
-    let answer_body = answer_node
-        .find(Class("js-post-body"))
-        .next()
-        .or_else(|| answer_node.find(Class("post-text")).next());
-    if let Some(instruction) = answer_body {
-        if !should_colorize {
-            return Some(instruction.text());
-        } else {
-            let mut formatted_answer: String = String::new();
-            for sub_node in instruction.children() {
-                match sub_node.name() {
-                    Some("pre") => formatted_answer
-                        .push_str(&(colorize_code(sub_node.text(), &question_tags) + "\n")),
-                    Some("code") => {
-                        formatted_answer.push_str(&colorize_code(sub_node.text(), &question_tags))
-                    }
-                    Some(_) => formatted_answer.push_str(&(sub_node.text() + "\n\n")),
-                    None => continue,
+    post_body(&answer_node).map(|body| render_post(&body, question_tags, config, base_url))
+}
+
+/// Render post body (of question or answer) with code colorized and text wrapped.
+fn render_post(
+    post_body: &Node,
+    question_tags: &[String],
+    config: &Config,
+    base_url: Option<&Url>,
+) -> String {
+    let should_colorize: bool = config.colorize();
+    let width: Option<usize> = config.width();
+    let mut formatted_answer: String = String::new();
+    for sub_node in post_body.children() {
+        match sub_node.name() {
+            Some("pre") => {
+                let tags: Vec<String> = code_tags(&sub_node, question_tags);
+                let label: Option<String> = match config.code_style() {
+                    CodeStyle::Plain => None,
+                    _ => syntax_name(&tags),
+                };
+                let code: String = if should_colorize {
+                    colorize_code(sub_node.text(), &tags)
+                } else {
+                    sub_node.text()
+                };
+                formatted_answer.push_str(&format_code(
+                    &code,
+                    label.as_deref(),
+                    config.code_style(),
+                    config.line_numbers(),
+                ));
+                if should_colorize {
+                    formatted_answer.push('\n');
+                }
+            }
+            Some("code") if should_colorize => {
+                formatted_answer.push_str(&colorize_code(sub_node.text(), question_tags))
+            }
+            Some(_) => {
+                formatted_answer.push_str(&wrap_text(&node_text(&sub_node, base_url), width));
+                if should_colorize {
+                    formatted_answer.push_str("\n\n");
                 }
             }
-            return Some(formatted_answer);
+            // keep the text between elements when output is not colorized, it makes
+            // output the same to the answer text.
+            None if !should_colorize => formatted_answer.push_str(&sub_node.text()),
+            None => continue,
         }
     }
-    None
+    formatted_answer
+}
+
+/// Split post body into paragraphs and code blocks, which are used to export answer.
+fn post_blocks(
+    post_body: &Node,
+    question_tags: &[String],
+    config: &Config,
+    base_url: Option<&Url>,
+) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    for sub_node in post_body.children() {
+        match sub_node.name() {
+            Some("pre") => blocks.push(Block::Code {
+                language: code_language(&sub_node, question_tags),
+                code: code_text(&sub_node, config),
+            }),
+            Some(list @ "ul") | Some(list @ "ol") => {
+                let items = sub_node.children().filter(|node| node.name() == Some("li"));
+                for (index, item) in items.enumerate() {
+                    let bullet: String = if list == "ol" {
+                        format!("{}. ", index + 1)
+                    } else {
+                        String::from("- ")
+                    };
+                    let mut inlines: Vec<Inline> = vec![Inline::Text(bullet)];
+                    inlines.extend(inline_blocks(&item, base_url));
+                    blocks.push(Block::Text(inlines));
+                }
+            }
+            Some(_) => blocks.push(Block::Text(inline_blocks(&sub_node, base_url))),
+            None if sub_node.text().trim().is_empty() => continue,
+            None => blocks.push(Block::Text(inline_blocks(&sub_node, base_url))),
+        }
+    }
+    blocks
+}
+
+/// Split content of paragraph into text, inline code and links.
+fn inline_blocks(node: &Node, base_url: Option<&Url>) -> Vec<Inline> {
+    match node.name() {
+        Some("code") => vec![Inline::Code(node.text())],
+        Some("a") => match node.attr("href").and_then(|href| base_url?.join(href).ok()) {
+            Some(url) => vec![Inline::Link {
+                text: node.text(),
+                url: url.into(),
+            }],
+            None => vec![Inline::Text(node.text())],
+        },
+        Some("br") => vec![Inline::Text(String::from("\n"))],
+        Some(_) => node
+            .children()
+            .flat_map(|child| inline_blocks(&child, base_url))
+            .collect(),
+        None => vec![Inline::Text(collapse_whitespace(&node.text()))],
+    }
+}
+
+/// Collapse consecutive whitespace into a single space, like browsers do.
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed: String = String::new();
+    let mut last_is_space: bool = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !last_is_space {
+                collapsed.push(' ');
+            }
+            last_is_space = true;
+        } else {
+            collapsed.push(c);
+            last_is_space = false;
+        }
+    }
+    collapsed
+}
+
+/// Get language of the code node, which is used to label exported code block.
+fn code_language(code_node: &Node, question_tags: &[String]) -> Option<String> {
+    code_tags(code_node, question_tags)
+        .into_iter()
+        .find(|tag| syntax_name(std::slice::from_ref(tag)).is_some())
+}
+
+/// Render comments of answer, every comment is rendered as a list item.
+fn render_comments(
+    comments: &[Node],
+    question_tags: &[String],
+    config: &Config,
+    base_url: Option<&Url>,
+) -> String {
+    let mut rendered: String = String::new();
+    for comment_node in comments {
+        let comment: Comment = Comment::parse(comment_node);
+        let text: String = match comment_copy(comment_node) {
+            Some(copy) => inline_text(&copy, question_tags, config, base_url),
+            None => comment.text.clone(),
+        };
+        let mut signature: Vec<String> = Vec::new();
+        signature.extend(comment.author);
+        signature.extend(comment.date);
+        signature.extend(comment.score.map(|score| format!("▲ {}", score)));
+
+        let line: String = if signature.is_empty() {
+            format!("  - {}", text.trim())
+        } else {
+            format!("  - {} — {}", text.trim(), signature.join(", "))
+        };
+        rendered.push_str(&wrap_text(&line, config.width()));
+        rendered.push('\n');
+    }
+    rendered
+}
+
+/// Get text of inline content (like comment), inline code in it is colorized, and links
+/// are made clickable if `base_url` is given.
+fn inline_text(
+    node: &Node,
+    question_tags: &[String],
+    config: &Config,
+    base_url: Option<&Url>,
+) -> String {
+    match node.name() {
+        Some("code") if config.colorize() => colorize_code(node.text(), question_tags),
+        Some("a") => node_text(node, base_url),
+        Some(_) => node
+            .children()
+            .map(|child| inline_text(&child, question_tags, config, base_url))
+            .collect(),
+        None => node.text(),
+    }
+}
+
+/// Get text of the given node.
+///
+/// If `base_url` is given, links inside the node are made clickable, and relative links are
+/// resolved against `base_url`.
+fn node_text(node: &Node, base_url: Option<&Url>) -> String {
+    let base_url: &Url = match base_url {
+        Some(base_url) => base_url,
+        None => return node.text(),
+    };
+    if node.name() == Some("a") {
+        if let Some(url) = node.attr("href").and_then(|href| base_url.join(href).ok()) {
+            return hyperlink(url.as_str(), &node.text());
+        }
+    }
+    if node.name().is_none() {
+        return node.text();
+    }
+    node.children()
+        .map(|child| node_text(&child, Some(base_url)))
+        .collect()
+}
+
+/// Get tags which are used to guess syntax of code, the language of user's project is put
+/// in front of question tags.
+fn syntax_tags(question_tags: &[String], config: &Config) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::with_capacity(question_tags.len() + 1);
+    tags.extend(config.language().map(String::from));
+    tags.extend(question_tags.iter().cloned());
+    tags
+}
+
+/// Get tags which can be used to colorize the given code node.
+///
+/// The code node may have language information in it's class, like
+/// `<pre class="lang-rust"><code>println!("hello");</code></pre>`.  If so, the language
+/// is put in front of question tags.
+fn code_tags(code_node: &Node, question_tags: &[String]) -> Vec<String> {
+    let pre_node: Option<Node> = if code_node.name() == Some("pre") {
+        Some(*code_node)
+    } else {
+        code_node.parent().filter(|node| node.name() == Some("pre"))
+    };
+    let language: Option<String> = pre_node
+        .into_iter()
+        .chain(std::iter::once(*code_node))
+        .filter_map(|node| node.attr("class"))
+        .flat_map(|class| class.split_whitespace())
+        .filter_map(|class| {
+            class
+                .strip_prefix("lang-")
+                .or_else(|| class.strip_prefix("language-"))
+        })
+        .find(|language| *language != "none")
+        .map(String::from);
+
+    let mut tags: Vec<String> = Vec::with_capacity(question_tags.len() + 1);
+    tags.extend(language);
+    tags.extend(question_tags.iter().cloned());
+    tags
 }
 
 /// Return links from the given stackoverflow links.
@@ -232,30 +1227,86 @@ fn parse_answer_detailed(
 /// # Arguments
 ///
 /// * `links` - stackoverflow links.
+/// * `hits` - search hits which provide title and snippet of links, title is extracted
+///   from link if it's not available.
 ///
 /// # Returns
 /// A list of links with splitter.  Which can directly output by the caller.
-fn answers_links_only(links: &[String], restricted_length: usize) -> String {
+fn answers_links_only(
+    links: &[String],
+    hits: &[SearchHit],
+    restricted_length: usize,
+    splitter: &str,
+    hyperlinks: bool,
+) -> String {
+    let (links, _) = take_question_links(links, restricted_length);
     let mut results: Vec<String> = Vec::new();
-    let mut links_iter = links.iter();
-    for _ in 0..restricted_length {
-        let next_link = links_iter.next();
-        match next_link {
-            Some(link) => {
-                if !link.contains("question") {
-                    continue;
-                }
-                let url: Url = Url::parse(link)
-                    .expect("Parse url failed, if you receive this message, please fire an issue.");
+    for link in links {
+        let url: Url = Url::parse(link)
+            .expect("Parse url failed, if you receive this message, please fire an issue.");
+
+        let hit: Option<&SearchHit> = hits.iter().find(|hit| &hit.link == link);
+        let title: String = hit
+            .and_then(|hit| hit.title.clone())
+            .unwrap_or_else(|| extract_question(url.path()));
+        let link: String = if hyperlinks {
+            hyperlink(link, link)
+        } else {
+            link.to_string()
+        };
+        let mut answer: String = format!("Title - {}\n{}", title, link);
+        if let Some(snippet) = hit.and_then(|hit| hit.snippet.as_ref()) {
+            answer.push('\n');
+            answer.push_str(snippet);
+        }
+        results.push(answer);
+    }
+    results.join(splitter)
+}
 
-                let answer: String =
-                    format!("Title - {}\n{}", extract_question(url.path()), *link,);
-                results.push(answer);
-            }
-            None => break,
+/// Take the first `numbers` question links from the given links.
+///
+/// Links which are not questions are skipped without taking up a slot, the number of
+/// links which are walked through is returned along with the taken links.
+fn take_question_links(links: &[String], numbers: usize) -> (Vec<&String>, usize) {
+    let mut taken: Vec<&String> = Vec::new();
+    let mut walked: usize = 0;
+    for link in links {
+        if taken.len() >= numbers {
+            break;
+        }
+        walked += 1;
+        if QuestionLink::parse(link).is_some() {
+            taken.push(link);
         }
     }
-    results.join(SPLITTER)
+    (taken, walked)
+}
+
+/// Return links from the given stackoverflow links as json array.
+///
+/// Every link is represented as `{"title": "question title", "link": "question link"}`,
+/// along with `snippet` if search hit of the link provides it.
+fn answers_links_json(
+    links: &[String],
+    hits: &[SearchHit],
+    restricted_length: usize,
+) -> Result<String> {
+    let (links, _) = take_question_links(links, restricted_length);
+    let results: Vec<JsonLink> = links
+        .into_iter()
+        .filter_map(|link| {
+            let hit: Option<&SearchHit> = hits.iter().find(|hit| &hit.link == link);
+            Url::parse(link).ok().map(|url| JsonLink {
+                title: hit
+                    .and_then(|hit| hit.title.clone())
+                    .unwrap_or_else(|| extract_question(url.path())),
+                link,
+                snippet: hit.and_then(|hit| hit.snippet.as_deref()),
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&results).map_err(|_| Error::from_parse("Serialize links failed"))
 }
 
 /// Extract question content.
@@ -264,7 +1315,7 @@ fn answers_links_only(links: &[String], restricted_length: usize) -> String {
 ///
 /// let question: String = extract_question("questions/user_id/the-specific-question");
 /// assert_eq!(question, String::from("the specific question"));
-fn extract_question(path: &str) -> String {
+pub(crate) fn extract_question(path: &str) -> String {
     // The stack overflow question have the following format
     // https://stackoverflow.com/questions/user_id/the-specific-question
     // we want to extract the question part out.
@@ -281,7 +1332,7 @@ fn extract_question(path: &str) -> String {
 /// let mut tags: Vec<String> = vec!["json", "rust"];
 /// sorted_tags(&mut tags);
 /// assert_eq!(tags, vec!["rust", "json"]);
-fn sort_tags(tags: &mut Vec<String>) {
+fn sort_tags(tags: &mut [String]) {
     // The list is get from SyntaxSet::load_defaults_newlines().syntaxes();
     // And picks some languages seems more popular.
     let tier_1_tags: HashSet<&str> = [
@@ -326,68 +1377,140 @@ fn sort_tags(tags: &mut Vec<String>) {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::config::{Config, OutputOption};
+    use crate::config::{CodeStyle, Config, HyperlinkOption, OutputOption};
 
     #[test]
     fn test_answer_links_only() {
         let links: Vec<String> = vec![String::from(
-            "https://stackoverflow.com/questions/test/how-to-write-function",
+            "https://stackoverflow.com/questions/12345/how-to-write-function",
         )];
         let restricted_length: usize = 1;
         let results: String = format!(
             "Title - {}\n{}",
             "how to write function",
-            "https://stackoverflow.com/questions/test/how-to-write-function"
+            "https://stackoverflow.com/questions/12345/how-to-write-function"
+        );
+        assert_eq!(
+            answers_links_only(&links, &[], restricted_length, SPLITTER, false),
+            results
         );
-        assert_eq!(answers_links_only(&links, restricted_length), results);
     }
 
     #[test]
     fn test_answer_links_only_when_contains_links_is_not_question() {
         let links: Vec<String> = vec![
             String::from("https://stackoverflow.com/tags/rust"), // this link shouldn't output
-            String::from("https://stackoverflow.com/questions/test/how-to-write-function"),
+            String::from("https://stackoverflow.com/questions/12345/how-to-write-function"),
         ];
         let restricted_length: usize = 4;
         let results: String = format!(
             "Title - {}\n{}",
             "how to write function",
-            "https://stackoverflow.com/questions/test/how-to-write-function"
+            "https://stackoverflow.com/questions/12345/how-to-write-function"
+        );
+        assert_eq!(
+            answers_links_only(&links, &[], restricted_length, SPLITTER, false),
+            results
         );
-        assert_eq!(answers_links_only(&links, restricted_length), results);
     }
 
     #[test]
-    fn test_answer_links_only_when_restricted_size_is_less_than_given_links() {
+    fn test_answer_links_only_when_skipped_links_come_first() {
         let links: Vec<String> = vec![
-            String::from("https://stackoverflow.com/questions/test/how-to-write-function"),
-            String::from("https://stackoverflow.com/questions/test/best-practise-for-rust"),
+            String::from("https://stackoverflow.com/tags/rust"),
+            String::from("https://stackoverflow.com/questions/12345/how-to-write-function"),
+            String::from("https://stackoverflow.com/questions/67890/best-practise-for-rust"),
         ];
-        let restricted_length: usize = 1;
         let results: String = format!(
             "Title - {}\n{}",
             "how to write function",
-            "https://stackoverflow.com/questions/test/how-to-write-function"
+            "https://stackoverflow.com/questions/12345/how-to-write-function"
         );
-        assert_eq!(answers_links_only(&links, restricted_length), results);
+        assert_eq!(answers_links_only(&links, &[], 1, SPLITTER, false), results);
+        let json: serde_json::Value =
+            serde_json::from_str(&answers_links_json(&links, &[], 1).unwrap()).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 1);
     }
 
-    #[test]
-    fn test_answer_links_only_when_restricted_size_is_large_than_given_links() {
+    #[tokio::test]
+    async fn test_collect_answers_links_consumed() {
         let links: Vec<String> = vec![
-            String::from("https://stackoverflow.com/questions/test/how-to-write-function"),
-            String::from("https://stackoverflow.com/questions/test/best-practise-for-rust"),
+            String::from("https://stackoverflow.com/tags/rust"),
+            String::from("https://stackoverflow.com/questions/12345/how-to-write-function"),
+            String::from("https://stackoverflow.com/questions/67890/best-practise-for-rust"),
         ];
-        let restricted_length: usize = 1000;
-        let results: String = format!(
-            "{}\n{}{}{}\n{}",
+        let conf: Config = Config::new(OutputOption::Links, 1, false);
+        let answers: Answers = collect_answers(&links, conf, Client::new(), &PageCache::empty())
+            .await
+            .unwrap();
+        assert_eq!(answers.answered_links, vec![links[1].clone()]);
+        assert_eq!(answers.consumed, 2);
+
+        // links run out before enough question links are taken.
+        let conf: Config = Config::new(OutputOption::Links, 5, false);
+        let answers: Answers = collect_answers(&links, conf, Client::new(), &PageCache::empty())
+            .await
+            .unwrap();
+        assert_eq!(answers.answered_links, links[1..].to_vec());
+        assert_eq!(answers.consumed, 3);
+    }
+
+    #[test]
+    fn test_answer_links_only_when_contains_answer_link() {
+        let links: Vec<String> = vec![String::from("https://stackoverflow.com/a/24680")];
+        let hits: Vec<SearchHit> = vec![SearchHit {
+            link: String::from("https://stackoverflow.com/a/24680"),
+            title: Some(String::from("How to write function")),
+            snippet: None,
+            rank: 1,
+        }];
+        let results: String = format!(
+            "Title - {}\n{}",
+            "How to write function", "https://stackoverflow.com/a/24680"
+        );
+        assert_eq!(
+            answers_links_only(&links, &hits, 1, SPLITTER, false),
+            results
+        );
+    }
+
+    #[test]
+    fn test_answer_links_only_when_restricted_size_is_less_than_given_links() {
+        let links: Vec<String> = vec![
+            String::from("https://stackoverflow.com/questions/12345/how-to-write-function"),
+            String::from("https://stackoverflow.com/questions/67890/best-practise-for-rust"),
+        ];
+        let restricted_length: usize = 1;
+        let results: String = format!(
+            "Title - {}\n{}",
+            "how to write function",
+            "https://stackoverflow.com/questions/12345/how-to-write-function"
+        );
+        assert_eq!(
+            answers_links_only(&links, &[], restricted_length, SPLITTER, false),
+            results
+        );
+    }
+
+    #[test]
+    fn test_answer_links_only_when_restricted_size_is_large_than_given_links() {
+        let links: Vec<String> = vec![
+            String::from("https://stackoverflow.com/questions/12345/how-to-write-function"),
+            String::from("https://stackoverflow.com/questions/67890/best-practise-for-rust"),
+        ];
+        let restricted_length: usize = 1000;
+        let results: String = format!(
+            "{}\n{}{}{}\n{}",
             "Title - how to write function",
-            "https://stackoverflow.com/questions/test/how-to-write-function",
+            "https://stackoverflow.com/questions/12345/how-to-write-function",
             SPLITTER,
             "Title - best practise for rust",
-            "https://stackoverflow.com/questions/test/best-practise-for-rust"
+            "https://stackoverflow.com/questions/67890/best-practise-for-rust"
+        );
+        assert_eq!(
+            answers_links_only(&links, &[], restricted_length, SPLITTER, false),
+            results
         );
-        assert_eq!(answers_links_only(&links, restricted_length), results);
     }
 
     #[test]
@@ -409,9 +1532,9 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
 
-        assert_eq!(answer.is_some(), true);
+        assert!(answer.is_some());
 
         if let Some(code) = answer {
             assert_eq!(code.trim(), String::from(r#"println!("hello world")"#));
@@ -437,9 +1560,9 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
 
-        assert_eq!(answer.is_some(), true);
+        assert!(answer.is_some());
 
         if let Some(code) = answer {
             assert_eq!(code.trim(), String::from(r#"println!("hello world")"#));
@@ -466,9 +1589,9 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
 
-        assert_eq!(answer.is_some(), true);
+        assert!(answer.is_some());
 
         if let Some(code) = answer {
             assert_eq!(code.trim(), String::from(r#"println!("hello world")"#));
@@ -492,9 +1615,9 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
 
-        assert_eq!(answer.is_none(), true);
+        assert!(answer.is_none());
     }
 
     #[test]
@@ -514,9 +1637,9 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
 
-        assert_eq!(answer.is_some(), true);
+        assert!(answer.is_some());
 
         if let Some(code) = answer {
             assert_eq!(code.trim(), String::from("goto"));
@@ -540,9 +1663,9 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::All, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
 
-        assert_eq!(answer.is_some(), true);
+        assert!(answer.is_some());
 
         if let Some(code) = answer {
             assert_eq!(code.trim(), String::from("answer goto here"));
@@ -566,9 +1689,9 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::All, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
 
-        assert_eq!(answer.is_some(), true);
+        assert!(answer.is_some());
 
         if let Some(code) = answer {
             assert_eq!(code.trim(), String::from("answer goto here"));
@@ -594,9 +1717,9 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::All, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
 
-        assert_eq!(answer.is_some(), true);
+        assert!(answer.is_some());
 
         if let Some(code) = answer {
             assert_eq!(code.trim(), String::from("print('go go go')"));
@@ -626,9 +1749,9 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::All, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
 
-        assert_eq!(answer.is_some(), true);
+        assert!(answer.is_some());
 
         if let Some(code) = answer {
             assert_eq!(code.trim(), String::from("answer higher here"));
@@ -657,7 +1780,7 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
-        let un_colorized_answer: String = parse_answer(page, &conf).unwrap();
+        let un_colorized_answer: String = parse_answer(&page, &conf).unwrap().content;
         let conf: Config = Config::new(OutputOption::OnlyCode, 1, true);
         let page: String = String::from(
             r#"
@@ -676,7 +1799,7 @@ mod test {
         </html>
         "#,
         );
-        let colorized_answer: String = parse_answer(page, &conf).unwrap();
+        let colorized_answer: String = parse_answer(&page, &conf).unwrap().content;
         assert_ne!(un_colorized_answer.trim(), colorized_answer.trim());
         assert!(un_colorized_answer.trim().len() < colorized_answer.trim().len());
     }
@@ -703,7 +1826,7 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::All, 1, false);
-        let un_colorized_answer: String = parse_answer(page, &conf).unwrap();
+        let un_colorized_answer: String = parse_answer(&page, &conf).unwrap().content;
         let conf: Config = Config::new(OutputOption::All, 1, true);
         let page: String = String::from(
             r#"
@@ -722,7 +1845,7 @@ mod test {
         </html>
         "#,
         );
-        let colorized_answer: String = parse_answer(page, &conf).unwrap();
+        let colorized_answer: String = parse_answer(&page, &conf).unwrap().content;
         assert_ne!(un_colorized_answer.trim(), colorized_answer.trim());
         assert!(un_colorized_answer.trim().len() < colorized_answer.trim().len());
     }
@@ -748,7 +1871,7 @@ mod test {
         "#,
         );
         let conf: Config = Config::new(OutputOption::All, 1, false);
-        let un_colorized_answer: String = parse_answer(page, &conf).unwrap();
+        let un_colorized_answer: String = parse_answer(&page, &conf).unwrap().content;
         let conf: Config = Config::new(OutputOption::All, 1, true);
         let page: String = String::from(
             r#"
@@ -767,26 +1890,719 @@ mod test {
         </html>
         "#,
         );
-        let colorized_answer: String = parse_answer(page, &conf).unwrap();
+        let colorized_answer: String = parse_answer(&page, &conf).unwrap().content;
         assert_ne!(un_colorized_answer.trim(), colorized_answer.trim());
         assert!(un_colorized_answer.trim().len() < colorized_answer.trim().len());
     }
 
+    #[test]
+    fn test_parse_answer_detailed_with_indent_code_style() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <body>
+                <div class="answer">
+                    <div class="js-vote-count">130</div>
+                    <div class="js-post-body">
+                        <p>answer here</p>
+                        <pre class="lang-python s-code-block"><code>print(1)
+</code></pre>
+                    </div>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config = Config::new(OutputOption::All, 1, false)
+            .with_code_style(CodeStyle::Indent)
+            .with_width(Some(0));
+        let answer: String = parse_answer(&page, &conf).unwrap().content;
+        assert!(answer.contains("answer here"));
+        assert!(answer.contains("    [Python]\n    print(1)\n"));
+    }
+
+    #[test]
+    fn test_parse_answer_detailed_with_width() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <body>
+                <div class="answer">
+                    <div class="js-vote-count">130</div>
+                    <div class="js-post-body">
+                        <p>answer goto here</p>
+                    </div>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config = Config::new(OutputOption::All, 1, false).with_width(Some(11));
+        let answer: String = parse_answer(&page, &conf).unwrap().content;
+        assert_eq!(answer.trim(), "answer goto\nhere");
+    }
+
+    #[test]
+    fn test_parse_answer_with_line_numbers() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <body>
+                <div class="answer">
+                    <div class="js-vote-count">130</div>
+                    <div class="js-post-body">
+                        <pre><code>a = 1
+b = 2
+</code></pre>
+                    </div>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false).with_line_numbers(true);
+        let answer: String = parse_answer(&page, &conf).unwrap().content;
+        assert_eq!(answer, "1 │ a = 1\n2 │ b = 2\n");
+    }
+
+    const MULTI_CODE_PAGE: &str = r#"
+        <html>
+            <body>
+                <div id="question" class="question">
+                    <div class="js-post-body"><pre><code>KeyError: 'name'</code></pre></div>
+                </div>
+                <div class="answer">
+                    <div class="js-vote-count">130</div>
+                    <div class="js-post-body">
+                        <p>You get</p>
+                        <pre><code>KeyError: 'name'</code></pre>
+                        <p>because the key is missing, use get instead:</p>
+                        <pre><code>value = data.get('name')</code></pre>
+                    </div>
+                </div>
+            </body>
+        </html>
+        "#;
+
+    #[test]
+    fn test_parse_answer_selects_most_relevant_code() {
+        let conf: Config =
+            Config::new(OutputOption::OnlyCode, 1, false).with_query("python dict get key");
+        let answer: ParsedAnswer = parse_answer(MULTI_CODE_PAGE, &conf).unwrap();
+        assert_eq!(answer.content, "value = data.get('name')");
+        assert_eq!(answer.text.code.unwrap(), "value = data.get('name')");
+    }
+
+    #[test]
+    fn test_parse_answer_with_code_index() {
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false).with_code_index(Some(1));
+        let answer: String = parse_answer(MULTI_CODE_PAGE, &conf).unwrap().content;
+        assert_eq!(answer, "KeyError: 'name'");
+
+        // fallback to the most relevant code block when index is out of range.
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false).with_code_index(Some(3));
+        let answer: String = parse_answer(MULTI_CODE_PAGE, &conf).unwrap().content;
+        assert_eq!(answer, "value = data.get('name')");
+    }
+
+    #[test]
+    fn test_parse_answer_with_all_code() {
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false).with_all_code(true);
+        let answer: String = parse_answer(MULTI_CODE_PAGE, &conf).unwrap().content;
+        assert_eq!(
+            answer,
+            "[Code 1]\nKeyError: 'name'\n[Code 2]\nvalue = data.get('name')"
+        );
+    }
+
+    #[test]
+    fn test_parse_answer_text_with_all_code() {
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false).with_all_code(true);
+        let text: AnswerText = parse_answer(MULTI_CODE_PAGE, &conf).unwrap().text;
+        assert_eq!(text.code.as_deref(), Some("KeyError: 'name'"));
+        assert_eq!(
+            text.codes,
+            Some(vec![
+                String::from("KeyError: 'name'"),
+                String::from("value = data.get('name')")
+            ])
+        );
+
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
+        let text: AnswerText = parse_answer(MULTI_CODE_PAGE, &conf).unwrap().text;
+        assert_eq!(text.code.as_deref(), Some("value = data.get('name')"));
+        assert!(text.codes.is_none());
+    }
+
+    #[test]
+    fn test_select_answer_with_broken_vote_count() {
+        let filter: SearchFilter = SearchFilter::default();
+        let doc: Document = Document::from(
+            r#"<div class="answer"><div class="js-post-body"><pre><code>a</code></pre></div></div>"#,
+        );
+        assert!(select_answer(&doc, &filter).is_err());
+
+        let page: &str = r#"<div class="answer"><div class="js-vote-count">1.2k</div></div>"#;
+        assert!(select_answer(&Document::from(page), &filter).is_err());
+        // the page is skipped rather than panic.
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
+        assert!(parse_answer(page, &conf).is_none());
+    }
+
+    #[test]
+    fn test_parse_all_answers() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <body>
+                <div class="answer" data-score="3">
+                    <div class="js-vote-count">3</div>
+                    <div class="js-post-body"><pre><code>second</code></pre></div>
+                </div>
+                <div class="answer" data-score="10">
+                    <div class="js-vote-count">10</div>
+                    <div class="js-post-body"><pre><code>first</code></pre></div>
+                </div>
+                <div class="answer" data-score="1">
+                    <div class="js-vote-count">1</div>
+                    <div class="js-post-body"><p>no code</p></div>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
+        let answers: Vec<String> = parse_all_answers(&page, &conf)
+            .into_iter()
+            .map(|answer| answer.content)
+            .collect();
+        assert_eq!(answers, vec!["first".to_string(), "second".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_linked_answer() {
+        let page: &str = r#"
+        <div class="answer" data-answerid="1">
+            <div class="js-vote-count">10</div>
+            <div class="js-post-body"><pre><code>most voted</code></pre></div>
+        </div>
+        <div class="answer" data-answerid="2">
+            <div class="js-vote-count">3</div>
+            <div class="js-post-body"><pre><code>linked</code></pre></div>
+        </div>"#;
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
+        assert_eq!(
+            parse_linked_answer(page, Some("2"), &conf).unwrap().content,
+            "linked"
+        );
+        assert_eq!(
+            parse_linked_answer(page, Some("3"), &conf).unwrap().content,
+            "most voted"
+        );
+        assert_eq!(
+            parse_linked_answer(page, None, &conf).unwrap().content,
+            "most voted"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_question_redirect() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <body>
+                <div id="question" class="question">
+                    <aside class="s-notice js-post-notice">
+                        <b>This question already has an answer here</b>:
+                        <ul><li><a href="/questions/7771011/how-to-parse-data-in-json">How to parse data in JSON?</a></li></ul>
+                    </aside>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
+        assert!(parse_answer(&page, &conf).is_none());
+        assert_eq!(
+            original_links(&page, "https://stackoverflow.com/questions/1/parse-json"),
+            vec![String::from(
+                "https://stackoverflow.com/questions/7771011/how-to-parse-data-in-json"
+            )]
+        );
+
+        let answer_page: &str = r#"<div class="answer"><div class="js-vote-count">3</div><div class="js-post-body"><pre><code>json.loads(data)</code></pre></div></div>"#;
+        let answer: ParsedAnswer = parse_answer(answer_page, &conf).unwrap();
+        let rendered: String = render_answer(
+            answer,
+            "https://stackoverflow.com/questions/7771011/how-to-parse-data-in-json",
+            Some("https://stackoverflow.com/questions/1/parse-json"),
+            &conf,
+        );
+        assert!(rendered.starts_with(
+            "- Answer from https://stackoverflow.com/questions/7771011/how-to-parse-data-in-json\n\
+             - Redirected from https://stackoverflow.com/questions/1/parse-json (closed as duplicate)\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_detailed_answer_of_duplicate_question() {
+        let link: &str = "https://stackoverflow.com/questions/1/parse-json";
+        let original: &str =
+            "https://stackoverflow.com/questions/7771011/how-to-parse-data-in-json";
+        let page_cache: PageCache = PageCache::empty();
+        page_cache.put(
+            link.to_string(),
+            format!(
+                r#"<div id="question" class="question">
+                    <aside class="s-notice js-post-notice">
+                        <b>This question already has an answer here</b>:
+                        <a href="{}">How to parse data in JSON?</a>
+                    </aside>
+                </div>"#,
+                original
+            ),
+        );
+        page_cache.put(
+            original.to_string(),
+            String::from(
+                r#"<div class="answer"><div class="js-vote-count">3</div><div class="js-post-body"><pre><code>json.loads(data)</code></pre></div></div>"#,
+            ),
+        );
+        let conf: Config =
+            Config::new(OutputOption::OnlyCode, 1, false).with_hyperlink(HyperlinkOption::Always);
+        let answers: Answers =
+            get_detailed_answer(&[link.to_string()], conf, Client::new(), page_cache)
+                .await
+                .unwrap();
+        assert_eq!(answers.answered_links, vec![link.to_string()]);
+        assert!(answers.output.contains(&format!(
+            "- Redirected from {} (closed as duplicate)\n",
+            hyperlink(link, link)
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_question_redirect_with_filter() {
+        let link: &str = "https://stackoverflow.com/questions/1/parse-json";
+        let original: &str =
+            "https://stackoverflow.com/questions/7771011/how-to-parse-data-in-json";
+        let page: String = format!(
+            r#"<div id="question" class="question">
+                <aside class="s-notice js-post-notice">
+                    <b>This question already has an answer here</b>:
+                    <a href="{}">How to parse data in JSON?</a>
+                </aside>
+            </div>"#,
+            original
+        );
+        let page_cache: PageCache = PageCache::empty();
+        page_cache.put(
+            original.to_string(),
+            String::from(
+                r#"<a class="post-tag">python</a>
+                <div class="answer accepted-answer"><div class="js-vote-count">3</div><div class="js-post-body"><pre><code>json.loads(data)</code></pre></div></div>"#,
+            ),
+        );
+        let conf: Config =
+            Config::new(OutputOption::OnlyCode, 1, false).with_filter(SearchFilter {
+                tags: vec![String::from("python")],
+                accepted_only: true,
+                ..SearchFilter::default()
+            });
+        let crawled: CrawledData = CrawledData::new(link.to_string(), page);
+        let (answer_link, redirected_from, answer) =
+            resolve_answer(&crawled, &conf, &Client::new(), &page_cache)
+                .await
+                .unwrap();
+        assert_eq!(answer_link, original);
+        assert_eq!(redirected_from, Some(link));
+        assert_eq!(answer.text.code.as_deref(), Some("json.loads(data)"));
+
+        // the original doesn't match filter.
+        let conf: Config = conf.with_filter(SearchFilter {
+            tags: vec![String::from("rust")],
+            ..SearchFilter::default()
+        });
+        assert!(resolve_answer(&crawled, &conf, &Client::new(), &page_cache)
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn test_parse_answer_with_filter() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <body>
+                <a class="post-tag">rust</a>
+                <div class="answer">
+                    <div class="js-vote-count">10</div>
+                    <time itemprop="dateCreated" datetime="2015-03-01T10:00:00"></time>
+                    <div class="js-post-body"><pre><code>old</code></pre></div>
+                </div>
+                <div class="answer accepted-answer">
+                    <div class="js-vote-count">0</div>
+                    <time itemprop="dateCreated" datetime="2021-06-01T10:00:00"></time>
+                    <div class="js-post-body"><pre><code>accepted</code></pre></div>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let filter = SearchFilter {
+            accepted_only: true,
+            ..SearchFilter::default()
+        };
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false).with_filter(filter);
+        assert_eq!(parse_answer(&page, &conf).unwrap().content, "accepted");
+
+        let filter = SearchFilter {
+            tags: vec![String::from("Rust")],
+            until: "2020-12-31".parse().ok(),
+            ..SearchFilter::default()
+        };
+        assert!(page_matches_filter(&page, &filter));
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false).with_filter(filter);
+        let answers: Vec<String> = parse_all_answers(&page, &conf)
+            .into_iter()
+            .map(|answer| answer.content)
+            .collect();
+        assert_eq!(answers, vec!["old".to_string()]);
+
+        let filter = SearchFilter {
+            min_score: Some(20),
+            ..SearchFilter::default()
+        };
+        assert!(!page_matches_filter(&page, &filter));
+        let filter = SearchFilter {
+            tags: vec![String::from("python")],
+            ..SearchFilter::default()
+        };
+        assert!(!page_matches_filter(&page, &filter));
+    }
+
+    #[test]
+    fn test_parse_answer_with_clean() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <body>
+                <div class="answer">
+                    <div class="js-vote-count">130</div>
+                    <div class="js-post-body">
+                        <pre><code>  $ pip install requests
+  Successfully installed requests
+</code></pre>
+                    </div>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false).with_clean(true);
+        let answer: ParsedAnswer = parse_answer(&page, &conf).unwrap();
+        assert_eq!(answer.content, "pip install requests\n");
+        assert_eq!(answer.text.code.unwrap(), "pip install requests\n");
+    }
+
+    #[test]
+    fn test_parse_answer_with_export() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <head><link rel="canonical" href="https://stackoverflow.com/questions/1/parse-json"></head>
+            <body>
+                <div id="question-header"><h1>How to parse json?</h1></div>
+                <a class="post-tag">python</a>
+                <div class="answer" data-answerid="2">
+                    <div class="js-vote-count">130</div>
+                    <div class="js-post-body">
+                        <p>Use <code>json</code>
+                        module, see <a href="/q/3">this</a>.</p>
+                        <ul><li>fast</li><li>simple</li></ul>
+                        <pre><code>json.loads(s)</code></pre>
+                    </div>
+                    <div class="post-signature owner">
+                        <time itemprop="dateCreated" datetime="2019-01-02T00:00:00"></time>
+                        <div class="user-details"><a href="/users/4">John</a></div>
+                    </div>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config =
+            Config::new(OutputOption::All, 1, false).with_export(Some(ExportFormat::Markdown));
+        let answer: ParsedAnswer = parse_answer(&page, &conf).unwrap();
+        assert_eq!(
+            export_parsed_answer(
+                &answer,
+                "https://stackoverflow.com/questions/1/parse-json",
+                None,
+                ExportFormat::Markdown
+            ),
+            "## [How to parse json?](https://stackoverflow.com/questions/1/parse-json)
+
+Use `json` module, see [this](https://stackoverflow.com/q/3).
+
+- fast
+
+- simple
+
+```python
+json.loads(s)
+```
+
+---
+
+- Source: [https://stackoverflow.com/a/2](https://stackoverflow.com/a/2)
+- Author: [John](https://stackoverflow.com/users/4)
+- License: [CC BY-SA 4.0](https://creativecommons.org/licenses/by-sa/4.0/)
+"
+        );
+    }
+
+    #[test]
+    fn test_code_tags_with_language_class() {
+        let doc: Document =
+            Document::from(r#"<pre class="lang-rust s-code-block"><code>let a = 1;</code></pre>"#);
+        let code_node: Node = doc.find(Name("code")).next().unwrap();
+        assert_eq!(
+            code_tags(&code_node, &["java".to_string()]),
+            vec!["rust".to_string(), "java".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_syntax_tags_prefer_project_language() {
+        let tags: Vec<String> = vec!["java".to_string()];
+        let config: Config = Config::new(OutputOption::All, 1, true);
+        assert_eq!(syntax_tags(&tags, &config), tags);
+        let config: Config = config.with_language(Some("rust"));
+        assert_eq!(
+            syntax_tags(&tags, &config),
+            vec!["rust".to_string(), "java".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_code_tags_without_language_class() {
+        let doc: Document =
+            Document::from(r#"<pre class="lang-none s-code-block"><code>let a = 1;</code></pre>"#);
+        let code_node: Node = doc.find(Name("pre")).next().unwrap();
+        assert_eq!(
+            code_tags(&code_node, &["java".to_string()]),
+            vec!["java".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_answer_links_only_with_search_hits() {
+        let links: Vec<String> = vec![
+            String::from("https://stackoverflow.com/questions/1/async-in-c"),
+            String::from("https://stackoverflow.com/questions/2/c-templates"),
+        ];
+        let hits: Vec<SearchHit> = vec![SearchHit {
+            link: links[0].clone(),
+            title: Some(String::from("Async in C#?")),
+            snippet: Some(String::from("Use await ...")),
+            rank: 1,
+        }];
+        assert_eq!(
+            answers_links_only(&links, &hits, 2, "\n--\n", false),
+            "Title - Async in C#?\nhttps://stackoverflow.com/questions/1/async-in-c\nUse await ...\n--\n\
+             Title - c templates\nhttps://stackoverflow.com/questions/2/c-templates"
+        );
+        let results: serde_json::Value =
+            serde_json::from_str(&answers_links_json(&links, &hits, 1).unwrap()).unwrap();
+        assert_eq!(
+            results,
+            serde_json::json!([{
+                "title": "Async in C#?",
+                "link": "https://stackoverflow.com/questions/1/async-in-c",
+                "snippet": "Use await ...",
+            }])
+        );
+    }
+
+    #[test]
+    fn test_answer_links_only_with_hyperlinks() {
+        let links: Vec<String> = vec![String::from(
+            "https://stackoverflow.com/questions/12345/how-to-write-function",
+        )];
+        let results: String = format!(
+            "Title - {}\n{}",
+            "how to write function",
+            "\x1b]8;;https://stackoverflow.com/questions/12345/how-to-write-function\x1b\\\
+            https://stackoverflow.com/questions/12345/how-to-write-function\x1b]8;;\x1b\\"
+        );
+        assert_eq!(answers_links_only(&links, &[], 1, SPLITTER, true), results);
+    }
+
+    #[test]
+    fn test_parse_answer_id() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <body>
+                <div id="answer-7771053" class="answer">
+                    <div class="js-vote-count">130</div>
+                    <div class="js-post-body">
+                        <p>answer here</p>
+                    </div>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config = Config::new(OutputOption::All, 1, false);
+        let answer: ParsedAnswer = parse_answer(&page, &conf).unwrap();
+        assert_eq!(answer.answer_id, Some(String::from("7771053")));
+    }
+
+    #[test]
+    fn test_answer_permalink() {
+        assert_eq!(
+            answer_permalink(
+                "https://stackoverflow.com/questions/7771011/parse-json-in-python",
+                Some("7771053")
+            ),
+            "https://stackoverflow.com/a/7771053"
+        );
+        assert_eq!(
+            answer_permalink(
+                "https://stackoverflow.com/questions/7771011/parse-json-in-python",
+                None
+            ),
+            "https://stackoverflow.com/questions/7771011/parse-json-in-python"
+        );
+    }
+
+    #[test]
+    fn test_parse_answer_detailed_with_hyperlinks() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <head>
+                <link rel="canonical" href="https://superuser.com/questions/1/test">
+            </head>
+            <body>
+                <div class="answer">
+                    <div class="js-vote-count">130</div>
+                    <div class="js-post-body">
+                        <p>see <a href="/q/2">this</a> answer</p>
+                    </div>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config =
+            Config::new(OutputOption::All, 1, false).with_hyperlink(HyperlinkOption::Always);
+        let answer: String = parse_answer(&page, &conf).unwrap().content;
+        assert_eq!(
+            answer.trim(),
+            "see \x1b]8;;https://superuser.com/q/2\x1b\\this\x1b]8;;\x1b\\ answer"
+        );
+    }
+
+    #[test]
+    fn test_parse_answer_with_question_and_comments() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <body>
+                <div id="question" class="question">
+                    <div class="js-post-body"><p>How to parse json?</p></div>
+                </div>
+                <div class="answer">
+                    <div class="js-vote-count">130</div>
+                    <div class="js-post-body">
+                        <pre><code>json.loads(data)</code></pre>
+                    </div>
+                    <ul class="comments-list">
+                        <li class="comment">
+                            <div class="comment-score"><span>12</span></div>
+                            <span class="comment-copy">use <code>json.load</code> for files</span>
+                            <a class="comment-user">John</a>
+                        </li>
+                    </ul>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false)
+            .with_question(true)
+            .with_comments(true);
+        let answer: ParsedAnswer = parse_answer(&page, &conf).unwrap();
+        assert_eq!(answer.content, "json.loads(data)");
+        assert_eq!(answer.question.unwrap().trim(), "How to parse json?");
+        assert_eq!(
+            answer.comments.unwrap(),
+            "  - use json.load for files — John, ▲ 12\n"
+        );
+        assert_eq!(answer.text.question.unwrap(), "How to parse json?");
+        assert_eq!(answer.text.code.unwrap(), "json.loads(data)");
+        assert_eq!(answer.text.comments.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_answer_without_question_and_comments() {
+        let page: String = String::from(
+            r#"
+        <html>
+            <body>
+                <div id="question" class="question">
+                    <div class="js-post-body"><p>How to parse json?</p></div>
+                </div>
+                <div class="answer">
+                    <div class="js-vote-count">130</div>
+                    <div class="js-post-body"><pre><code>json.loads(data)</code></pre></div>
+                </div>
+            </body>
+        </html>
+        "#,
+        );
+        let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
+        let answer: ParsedAnswer = parse_answer(&page, &conf).unwrap();
+        assert!(answer.question.is_none());
+        assert!(answer.comments.is_none());
+        assert!(answer.text.comments.is_none());
+    }
+
+    #[test]
+    fn test_answers_links_json() {
+        let links: Vec<String> = vec![
+            String::from("https://stackoverflow.com/tags/rust"),
+            String::from("https://stackoverflow.com/questions/12345/how-to-write-function"),
+        ];
+        let results: serde_json::Value =
+            serde_json::from_str(&answers_links_json(&links, &[], 2).unwrap()).unwrap();
+        assert_eq!(
+            results,
+            serde_json::json!([{
+                "title": "how to write function",
+                "link": "https://stackoverflow.com/questions/12345/how-to-write-function"
+            }])
+        );
+    }
+
     #[test]
     fn test_parse_answer_when_no_answers_available() {
         let page: String = String::from("");
         let conf: Config = Config::new(OutputOption::OnlyCode, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
 
-        assert_eq!(answer.is_none(), true);
+        assert!(answer.is_none());
     }
 
     #[test]
     fn test_parse_answer_detailed_when_no_answers_available() {
         let page: String = String::from("");
         let conf: Config = Config::new(OutputOption::All, 1, false);
-        let answer: Option<String> = parse_answer(page, &conf);
-        assert_eq!(answer.is_none(), true);
+        let answer: Option<String> = parse_answer(&page, &conf).map(|answer| answer.content);
+        assert!(answer.is_none());
     }
 
     #[test]
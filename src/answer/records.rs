@@ -1,10 +1,12 @@
 use crate::error::{Error, Result};
+use crate::link::cache_key;
 use bincode::{deserialize_from, serialize_into};
 use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// The answer record relative information is integrated here.
@@ -97,12 +99,13 @@ impl AnswerRecordsCache {
     ///
     /// # Arguments
     ///
-    /// * `link` - link contains stackoverflow question.
+    /// * `link` - link contains stackoverflow question, links to the same question share
+    ///   the same page.
     ///
     /// # Returns
     /// Return cached page if we can find it and it's not too old, else returns None.
     pub fn get(&mut self, link: &str) -> Option<&String> {
-        let possible_page: Option<&mut AnswerRecord> = self.0.get_mut(link);
+        let possible_page: Option<&mut AnswerRecord> = self.0.get_mut(&cache_key(link));
         match possible_page {
             // if we can find relative record
             Some(record) => {
@@ -131,8 +134,9 @@ impl AnswerRecordsCache {
     /// * `link` - link to page.
     /// * `page` - the content of page.
     pub fn put(&mut self, link: String, page: String) {
-        let record: AnswerRecord = AnswerRecord::new(link.clone(), page);
-        self.0.insert(link, record);
+        let key: String = cache_key(&link);
+        let record: AnswerRecord = AnswerRecord::new(key.clone(), page);
+        self.0.insert(key, record);
     }
 
     /// Save the data into local file.
@@ -153,8 +157,7 @@ impl AnswerRecordsCache {
                 .collect();
             link_hit_counter.sort();
             // Just truncate data to MAX_SIZE / 2.
-            for i in 0..MAX_SIZE / 2 {
-                let (_, link) = &link_hit_counter[i];
+            for (_, link) in link_hit_counter.iter().take(MAX_SIZE / 2) {
                 self.0.remove(link);
             }
         }
@@ -182,7 +185,7 @@ impl AnswerRecordsCache {
 
     fn create_file_if_not_existed(cache_directory: &PathBuf) -> Result<PathBuf> {
         if !cache_directory.exists() {
-            fs::create_dir_all(&cache_directory).unwrap();
+            fs::create_dir_all(cache_directory).unwrap();
         }
 
         let answers = cache_directory.join("answers_v2");
@@ -193,6 +196,61 @@ impl AnswerRecordsCache {
     }
 }
 
+/// Page cache which is loaded once and shared between queries.
+///
+/// It's cheap to clone, all clones refer to the same cache, so a long running session
+/// (like `hors shell`) doesn't need to reload local cache file for every query.
+#[derive(Clone, Debug)]
+pub struct PageCache(Arc<Mutex<AnswerRecordsCache>>);
+
+impl PageCache {
+    /// Load pages from local cache file, an empty cache is used if loading failed.
+    pub fn load() -> PageCache {
+        debug!("Try to load cache from local cache file.");
+        let records_cache: AnswerRecordsCache = AnswerRecordsCache::load().unwrap_or_else(|err| {
+            warn!("Can't load cache from local cache file, errmsg {:?}", err);
+            AnswerRecordsCache::load_empty()
+        });
+        debug!("Load cache complete.");
+        PageCache::new(records_cache)
+    }
+
+    fn new(records_cache: AnswerRecordsCache) -> PageCache {
+        PageCache(Arc::new(Mutex::new(records_cache)))
+    }
+
+    /// Create an empty cache which is not loaded from local cache file.
+    #[cfg(test)]
+    pub(crate) fn empty() -> PageCache {
+        PageCache::new(AnswerRecordsCache::load_empty())
+    }
+
+    /// Get cached page of the given link, please check `AnswerRecordsCache::get` for details.
+    pub fn get(&self, link: &str) -> Option<String> {
+        self.0.lock().unwrap().get(link).cloned()
+    }
+
+    /// Put page to cache, please check `AnswerRecordsCache::put` for details.
+    pub fn put(&self, link: String, page: String) {
+        self.0.lock().unwrap().put(link, page)
+    }
+
+    /// Save pages into local cache file.
+    pub fn save(&self) -> Result<()> {
+        self.0.lock().unwrap().save()
+    }
+
+    /// Remove all pages, local cache file is removed too.
+    pub fn clear(&self) -> Result<()> {
+        let mut records_cache = self.0.lock().unwrap();
+        AnswerRecordsCache::clear()?;
+        *records_cache = AnswerRecordsCache::load_empty();
+        // create an empty cache file, so pages can be saved again later.
+        AnswerRecordsCache::create_file_if_not_existed(&AnswerRecordsCache::get_cache_dir()?)?;
+        Ok(())
+    }
+}
+
 /// Remove local cache file if it's existed.
 pub fn clear_local_cache() -> Result<()> {
     AnswerRecordsCache::clear()
@@ -225,7 +283,7 @@ mod tests {
             .expect("Time went beckwards")
             .as_secs();
         let test_record = AnswerRecord::new(link, page);
-        assert_eq!(test_record.is_too_old(current_time), false);
+        assert!(!test_record.is_too_old(current_time));
 
         let half_month_and_one_second: u64 = 3600 * 24 * 15 + 1;
         let time_after_half_month: u64 = SystemTime::now()
@@ -233,13 +291,13 @@ mod tests {
             .expect("Time went beckwards")
             .as_secs()
             + half_month_and_one_second;
-        assert_eq!(test_record.is_too_old(time_after_half_month), true);
+        assert!(test_record.is_too_old(time_after_half_month));
     }
 
     #[test]
     fn test_answer_record_cache_empty() {
         let record_cache: AnswerRecordsCache = AnswerRecordsCache::load_empty();
-        assert_eq!(record_cache.0.is_empty(), true);
+        assert!(record_cache.0.is_empty());
     }
 
     #[test]
@@ -274,13 +332,35 @@ mod tests {
     }
 
     #[test]
-    fn test_answer_record_get_when_key_is_not_existed() {
+    fn test_answer_record_get_by_other_link_of_question() {
         let mut record_cache: AnswerRecordsCache = AnswerRecordsCache::load_empty();
+        record_cache.put(
+            "https://stackoverflow.com/questions/12/parse-json".to_string(),
+            "<html></html>".to_string(),
+        );
         assert_eq!(
-            record_cache
-                .get(&String::from("http://test_link"))
-                .is_none(),
-            true
+            record_cache.get("http://m.stackoverflow.com/questions/12/parse-js?lq=1#34"),
+            Some(&String::from("<html></html>"))
         );
     }
+
+    #[test]
+    fn test_page_cache_is_shared_between_clones() {
+        let page_cache: PageCache = PageCache::new(AnswerRecordsCache::load_empty());
+        page_cache
+            .clone()
+            .put("http://test_link".to_string(), "<html></html>".to_string());
+        assert_eq!(
+            page_cache.get("http://test_link"),
+            Some(String::from("<html></html>"))
+        );
+    }
+
+    #[test]
+    fn test_answer_record_get_when_key_is_not_existed() {
+        let mut record_cache: AnswerRecordsCache = AnswerRecordsCache::load_empty();
+        assert!(record_cache
+            .get(&String::from("http://test_link"))
+            .is_none());
+    }
 }
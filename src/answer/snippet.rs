@@ -0,0 +1,392 @@
+//! Select the most relevant code snippet from answer.
+//!
+//! An answer may contain many code blocks, and the first one is often an error message
+//! which is quoted from question, rather than the fix.  So every code block is scored by
+//! the overlap with query terms, it's length and position, and the best one is selected.
+//!
+//! The selected snippet can be cleaned up too, so it can be pasted or piped to shell directly.
+
+use select::node::Node;
+use select::predicate::Name;
+use std::collections::HashSet;
+
+/// Tab stop width when expanding tabs in code.
+const TAB_WIDTH: usize = 4;
+
+/// Prompts of known REPLs and shells, which are followed by user input.
+const PROMPTS: [&str; 8] = [
+    "$ ",
+    ">>> ",
+    "scala> ",
+    "ghci> ",
+    "Prelude> ",
+    "sqlite> ",
+    "mysql> ",
+    "postgres=# ",
+];
+
+/// Words which are too common in queries to make sense.
+const STOP_WORDS: [&str; 16] = [
+    "a", "an", "and", "do", "does", "for", "how", "i", "in", "is", "of", "on", "the", "to", "what",
+    "with",
+];
+
+/// Get all code blocks in the answer.
+///
+/// `pre` elements are code blocks, if there is no `pre` element, inline `code` elements
+/// are returned instead.
+pub fn code_blocks<'a>(answer_node: &Node<'a>) -> Vec<Node<'a>> {
+    let blocks: Vec<Node> = answer_node.find(Name("pre")).collect();
+    if !blocks.is_empty() {
+        return blocks;
+    }
+    answer_node.find(Name("code")).collect()
+}
+
+/// Select the index of most relevant code block.
+///
+/// # Arguments
+///
+/// * `blocks` - text of code blocks in answer.
+/// * `query` - user input query.
+/// * `question_blocks` - text of code blocks in question, code which is quoted from question is
+///   less likely to be the fix.
+///
+/// # Returns
+///
+/// The index of best code block, or None if there are no code blocks.
+pub fn best_code_index(
+    blocks: &[String],
+    query: &str,
+    question_blocks: &[String],
+) -> Option<usize> {
+    let terms: HashSet<String> = query_terms(query);
+    let mut best: Option<(usize, f64)> = None;
+    for (index, block) in blocks.iter().enumerate() {
+        let score = score_code(block, index, blocks.len(), &terms, question_blocks);
+        debug!("Score of code block {}: {}", index, score);
+        match best {
+            Some((_, best_score)) if best_score >= score => {}
+            _ => best = Some((index, score)),
+        }
+    }
+    best.map(|(index, _)| index)
+}
+
+/// Score code block, the higher the better.
+fn score_code(
+    code: &str,
+    position: usize,
+    total: usize,
+    terms: &HashSet<String>,
+    question_blocks: &[String],
+) -> f64 {
+    let code: &str = code.trim();
+    if code.is_empty() {
+        return f64::MIN;
+    }
+
+    // longer code is more likely to be a complete solution, but don't let huge code wins.
+    let lines: usize = code.lines().count();
+    let length: f64 = lines.min(10) as f64 / 10.0;
+
+    // earlier code is a little better.
+    let position: f64 = (total - position) as f64 / total as f64;
+
+    // error message is always relative to query, but it's not the solution, so query terms
+    // overlap is ignored for it.
+    if lines == 1 && looks_like_error(code) {
+        return length + 0.5 * position - 1.0;
+    }
+
+    // query terms overlap, which is the most important factor.
+    let overlap: f64 = if terms.is_empty() {
+        0.0
+    } else {
+        let code_terms: HashSet<String> = tokenize(code).collect();
+        terms.intersection(&code_terms).count() as f64 / terms.len() as f64
+    };
+
+    let mut score: f64 = 3.0 * overlap + length + 0.5 * position;
+    if question_blocks
+        .iter()
+        .any(|question_code| question_code.trim() == code)
+    {
+        score -= 2.0;
+    }
+    score
+}
+
+/// Return true if the code looks like an error message.
+fn looks_like_error(code: &str) -> bool {
+    ["error", "exception", "traceback", "warning:"]
+        .iter()
+        .any(|keyword| code.to_lowercase().contains(keyword))
+}
+
+/// Split query into lower-cased terms, common words are removed.
+fn query_terms(query: &str) -> HashSet<String> {
+    tokenize(query)
+        .filter(|term| !STOP_WORDS.contains(&term.as_str()))
+        .collect()
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| token.len() > 1)
+        .map(|token| token.to_lowercase())
+}
+
+/// A line of code snippet, which is classified by REPL or shell prompt.
+#[derive(Debug, PartialEq)]
+enum SnippetLine<'a> {
+    /// The input line which begins with a prompt, the prompt is stripped.
+    Input(&'a str),
+    /// The continuation of previous input line, like `... ` in python.
+    Continuation(&'a str),
+    /// A line without prompt, which may be output of REPL or just code.
+    Other(&'a str),
+}
+
+/// Clean up code snippet, so it can be pasted or piped to shell directly.
+///
+/// The following things are done:
+/// 1. expand tabs to spaces.
+/// 2. strip shell and REPL prompts, e.g: `$ `, `>>> `, `PS C:\> `.  If there are prompts in
+///    snippet, the lines without prompts are regarded as output and removed.
+/// 3. remove trailing whitespace and leading/trailing blank lines.
+/// 4. remove common indentation.
+pub fn clean_code(code: &str) -> String {
+    let expanded: Vec<String> = code.lines().map(expand_tabs).collect();
+    let lines: Vec<SnippetLine> = expanded.iter().map(|line| classify_line(line)).collect();
+
+    let has_prompt: bool = lines
+        .iter()
+        .any(|line| matches!(line, SnippetLine::Input(_)));
+    let mut cleaned: Vec<&str> = Vec::new();
+    for (line, raw) in lines.into_iter().zip(expanded.iter()) {
+        match line {
+            SnippetLine::Input(text) | SnippetLine::Continuation(text) if has_prompt => {
+                cleaned.push(text)
+            }
+            SnippetLine::Other(text) if has_prompt => {
+                // shell command may be continued by a trailing backslash.
+                if cleaned
+                    .last()
+                    .is_some_and(|last| last.trim_end().ends_with('\\'))
+                {
+                    cleaned.push(text);
+                }
+            }
+            // `...` may be a part of code when there are no prompts, keep the line as it is.
+            _ => cleaned.push(raw),
+        }
+    }
+
+    let mut cleaned: Vec<&str> = cleaned.iter().map(|line| line.trim_end()).collect();
+    while cleaned.last().is_some_and(|line| line.is_empty()) {
+        cleaned.pop();
+    }
+    let first_line: usize = cleaned
+        .iter()
+        .position(|line| !line.is_empty())
+        .unwrap_or(cleaned.len());
+    let cleaned: &[&str] = &cleaned[first_line..];
+
+    // only spaces are counted, other whitespaces like NBSP are multi-bytes and kept.
+    let indent: usize = cleaned
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    let mut result: String = cleaned
+        .iter()
+        .map(|line| if line.is_empty() { "" } else { &line[indent..] })
+        .collect::<Vec<&str>>()
+        .join("\n");
+    if code.ends_with('\n') && !result.is_empty() {
+        result.push('\n');
+    }
+    result
+}
+
+/// Expand tabs in the given line to spaces.
+fn expand_tabs(line: &str) -> String {
+    let mut expanded: String = String::new();
+    let mut column: usize = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces: usize = TAB_WIDTH - column % TAB_WIDTH;
+            expanded.push_str(&" ".repeat(spaces));
+            column += spaces;
+        } else {
+            expanded.push(c);
+            column += 1;
+        }
+    }
+    expanded
+}
+
+fn classify_line(line: &str) -> SnippetLine<'_> {
+    let content: &str = line.trim_start();
+    for prompt in PROMPTS.iter() {
+        if let Some(rest) = content.strip_prefix(prompt) {
+            return SnippetLine::Input(rest);
+        }
+    }
+    // prompts without trailing space, which are followed by an empty input.
+    if content == "$" || content == ">>>" {
+        return SnippetLine::Input("");
+    }
+    // python continuation, e.g: `...     print(a)`.
+    if let Some(rest) = content.strip_prefix("... ") {
+        return SnippetLine::Continuation(rest);
+    }
+    if content == "..." {
+        return SnippetLine::Continuation("");
+    }
+    // ipython prompts, e.g: `In [1]: a = 1`, `   ...: b = 2`.
+    if let Some(rest) = content.strip_prefix("In [") {
+        if let Some(index) = rest.find("]: ") {
+            if rest[..index].chars().all(|c| c.is_ascii_digit()) {
+                return SnippetLine::Input(&rest[index + 3..]);
+            }
+        }
+    }
+    if let Some(rest) = content.strip_prefix("...: ") {
+        return SnippetLine::Continuation(rest);
+    }
+    // prompts which contain current location, e.g: `PS C:\Users> `, `irb(main):001:0> `,
+    // `iex(1)> `.
+    if ["PS ", "irb(", "iex("]
+        .iter()
+        .any(|prefix| content.starts_with(prefix))
+    {
+        if let Some(index) = content.find("> ") {
+            return SnippetLine::Input(&content[index + 2..]);
+        }
+    }
+    SnippetLine::Other(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use select::document::Document;
+
+    #[test]
+    fn test_code_blocks() {
+        let doc = Document::from("<div><p><code>a</code></p><pre>b</pre><pre>c</pre></div>");
+        let answer: Node = doc.find(Name("div")).next().unwrap();
+        let blocks: Vec<String> = code_blocks(&answer).iter().map(|n| n.text()).collect();
+        assert_eq!(blocks, vec!["b".to_string(), "c".to_string()]);
+    }
+
+    #[test]
+    fn test_code_blocks_when_only_inline_code() {
+        let doc = Document::from("<div><p><code>a</code> and <code>b</code></p></div>");
+        let answer: Node = doc.find(Name("div")).next().unwrap();
+        let blocks: Vec<String> = code_blocks(&answer).iter().map(|n| n.text()).collect();
+        assert_eq!(blocks, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn test_best_code_index_prefers_query_terms() {
+        let blocks = vec![
+            String::from("let x = 1;"),
+            String::from("let v: Value = serde_json::from_str(data)?;"),
+        ];
+        assert_eq!(
+            best_code_index(&blocks, "how to parse json with serde", &[]),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_best_code_index_skips_error_message() {
+        let blocks = vec![
+            String::from("TypeError: 'NoneType' object is not subscriptable"),
+            String::from("if data is not None:\n    print(data[0])"),
+        ];
+        assert_eq!(
+            best_code_index(&blocks, "nonetype subscriptable", &[]),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_best_code_index_skips_code_from_question() {
+        let blocks = vec![String::from("a = b"), String::from("a = c")];
+        assert_eq!(
+            best_code_index(&blocks, "", &[String::from("a = b")]),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_best_code_index_prefers_first_when_tied() {
+        let blocks = vec![String::from("a = b"), String::from("a = c")];
+        assert_eq!(best_code_index(&blocks, "", &[]), Some(0));
+    }
+
+    #[test]
+    fn test_best_code_index_without_blocks() {
+        assert_eq!(best_code_index(&[], "query", &[]), None);
+    }
+
+    #[test]
+    fn test_clean_code_strips_shell_prompts_and_output() {
+        let code =
+            "$ cargo new hello\n     Created binary (application) `hello` package\n$ cd hello\n";
+        assert_eq!(clean_code(code), "cargo new hello\ncd hello\n");
+    }
+
+    #[test]
+    fn test_clean_code_keeps_backslash_continuation() {
+        let code = "$ docker run \\\n    -it ubuntu\n";
+        assert_eq!(clean_code(code), "docker run \\\n    -it ubuntu\n");
+    }
+
+    #[test]
+    fn test_clean_code_strips_python_repl() {
+        let code = ">>> for i in range(2):\n...     print(i)\n...\n0\n1\n>>> a = 1";
+        assert_eq!(
+            clean_code(code),
+            "for i in range(2):\n    print(i)\n\na = 1"
+        );
+    }
+
+    #[test]
+    fn test_clean_code_strips_powershell_and_ipython_prompts() {
+        assert_eq!(clean_code("PS C:\\Users> Get-ChildItem"), "Get-ChildItem");
+        assert_eq!(
+            clean_code("In [1]: a = 1\n\nIn [2]: a\nOut[2]: 1"),
+            "a = 1\na"
+        );
+    }
+
+    #[test]
+    fn test_clean_code_dedent_and_expand_tabs() {
+        let code = "\n    fn main() {\n    \tprintln!(\"hi\");   \n    }\n\n";
+        assert_eq!(clean_code(code), "fn main() {\n    println!(\"hi\");\n}\n");
+    }
+
+    #[test]
+    fn test_clean_code_without_prompts() {
+        assert_eq!(clean_code("a = 1\nprint(a)\n"), "a = 1\nprint(a)\n");
+        assert_eq!(
+            clean_code("def f():\n    ...\nx[...: 2]"),
+            "def f():\n    ...\nx[...: 2]"
+        );
+    }
+
+    #[test]
+    fn test_clean_code_with_nbsp_indent() {
+        assert_eq!(
+            clean_code("  \u{a0}a = 1\n  \u{a0}\u{a0}b = 2"),
+            "\u{a0}a = 1\n\u{a0}\u{a0}b = 2"
+        );
+        assert_eq!(clean_code("\u{a0}a = 1"), "\u{a0}a = 1");
+    }
+}
@@ -0,0 +1,215 @@
+//! Personal stash of answers.
+//!
+//! Different from `AnswerRecordsCache`, stashed answers never expire and never get pruned,
+//! they're removed only when user asks to.  Stash is saved as json in data directory,
+//! so it's easy to backup and survives cache cleaning.
+
+use super::layout::strip_escapes;
+use crate::error::Result;
+use crate::utils::{cache_dir, data_dir, read_json, write_json};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File name of stash in data directory.
+const STASH_FILE: &str = "stash.json";
+/// File name of the last shown answer in cache directory.
+const LAST_ANSWER_FILE: &str = "last_answer.json";
+
+/// An answer which is shown to user, it can be stashed later.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct StashedAnswer {
+    /// user input query.
+    pub query: String,
+    /// the links where answers come from.
+    pub links: Vec<String>,
+    /// the answers which are shown to user, colors and hyperlinks are removed.
+    pub content: String,
+    /// when it was shown, as seconds since unix epoch.
+    pub created_time: u64,
+}
+
+impl StashedAnswer {
+    pub fn new(query: String, links: Vec<String>, content: String) -> StashedAnswer {
+        StashedAnswer {
+            query,
+            links,
+            content: strip_escapes(&content),
+            created_time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Time went beckwards")
+                .as_secs(),
+        }
+    }
+
+    /// Remember the answer as the last shown answer, so it can be stashed later.
+    pub fn save_as_last(&self) -> Result<()> {
+        let mut path: PathBuf = cache_dir()?;
+        fs::create_dir_all(&path)?;
+        path.push(LAST_ANSWER_FILE);
+        write_json(&path, self)
+    }
+
+    /// Load the last shown answer.
+    ///
+    /// # Returns
+    ///
+    /// The last shown answer, or None if there is no answer shown yet.
+    pub fn load_last() -> Result<Option<StashedAnswer>> {
+        let mut path: PathBuf = cache_dir()?;
+        path.push(LAST_ANSWER_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        read_json(&path).map(Some)
+    }
+
+    /// Return true if the answer contains the given keyword, case is ignored.
+    fn contains(&self, keyword: &str) -> bool {
+        let keyword: String = keyword.to_lowercase();
+        self.query.to_lowercase().contains(&keyword)
+            || strip_escapes(&self.content)
+                .to_lowercase()
+                .contains(&keyword)
+            || self.links.iter().any(|link| link.contains(&keyword))
+    }
+}
+
+/// Stashed answers, which are indexed by name.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Stash(BTreeMap<String, StashedAnswer>);
+
+impl Stash {
+    /// Load stash from data directory.
+    ///
+    /// # Returns
+    ///
+    /// The stash, it's empty if nothing is stashed yet.
+    pub fn load() -> Result<Stash> {
+        Self::load_from(&Self::get_stash_path()?)
+    }
+
+    /// Load stash from the given file.
+    pub fn load_from(path: &Path) -> Result<Stash> {
+        if !path.exists() {
+            return Ok(Stash::default());
+        }
+        read_json(path)
+    }
+
+    /// Save stash into data directory.
+    pub fn save(&self) -> Result<()> {
+        let path: PathBuf = Self::get_stash_path()?;
+        if let Some(stash_dir) = path.parent() {
+            fs::create_dir_all(stash_dir)?;
+        }
+        self.save_to(&path)
+    }
+
+    /// Save stash into the given file.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        write_json(path, self)
+    }
+
+    /// Stash answer with the given name.
+    ///
+    /// # Returns
+    ///
+    /// The answer which is stashed with the same name before.
+    pub fn put(&mut self, name: String, answer: StashedAnswer) -> Option<StashedAnswer> {
+        self.0.insert(name, answer)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&StashedAnswer> {
+        self.0.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<StashedAnswer> {
+        self.0.remove(name)
+    }
+
+    /// Iterate over stashed answers, ordered by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &StashedAnswer)> {
+        self.0.iter()
+    }
+
+    /// Search stashed answers whose name, query, content or links contain the keyword.
+    pub fn search<'a>(
+        &'a self,
+        keyword: &'a str,
+    ) -> impl Iterator<Item = (&'a String, &'a StashedAnswer)> {
+        self.0.iter().filter(move |(name, answer)| {
+            name.to_lowercase().contains(&keyword.to_lowercase()) || answer.contains(keyword)
+        })
+    }
+
+    fn get_stash_path() -> Result<PathBuf> {
+        let mut path: PathBuf = data_dir()?;
+        path.push(STASH_FILE);
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn answer(query: &str, content: &str) -> StashedAnswer {
+        StashedAnswer::new(
+            query.to_string(),
+            vec![String::from("https://stackoverflow.com/questions/1/q")],
+            content.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_stash_put_and_remove() {
+        let mut stash: Stash = Stash::default();
+        assert!(stash
+            .put("json".to_string(), answer("parse json", "a"))
+            .is_none());
+        assert!(stash
+            .put("json".to_string(), answer("parse json", "b"))
+            .is_some());
+        assert_eq!(stash.get("json").unwrap().content, "b");
+        assert!(stash.remove("json").is_some());
+        assert!(stash.get("json").is_none());
+    }
+
+    #[test]
+    fn test_stashed_answer_without_colors() {
+        let answer: StashedAnswer = answer("parse json", "\x1b[31mjson.loads(s)\x1b[0m");
+        assert_eq!(answer.content, "json.loads(s)");
+    }
+
+    #[test]
+    fn test_stash_search() {
+        let mut stash: Stash = Stash::default();
+        stash.put("json".to_string(), answer("parse json", "json.loads(s)"));
+        stash.put("git".to_string(), answer("undo commit", "git reset HEAD~"));
+        let names: Vec<&String> = stash.search("RESET").map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["git"]);
+        let names: Vec<&String> = stash.search("json").map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["json"]);
+    }
+
+    #[test]
+    fn test_stash_save_and_load() {
+        let path: PathBuf = env::temp_dir().join(format!("hors_stash_test_{}", std::process::id()));
+        let mut stash: Stash = Stash::default();
+        stash.put("json".to_string(), answer("parse json", "json.loads(s)"));
+        stash.save_to(&path).unwrap();
+        let loaded: Stash = Stash::load_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, stash);
+    }
+
+    #[test]
+    fn test_stash_load_when_file_is_not_existed() {
+        let path: PathBuf = env::temp_dir().join("hors_stash_test_not_existed");
+        assert_eq!(Stash::load_from(&path).unwrap(), Stash::default());
+    }
+}
@@ -1,17 +1,34 @@
 #[macro_use]
 extern crate log;
 
-use clap::{self, Parser};
-use hors::{self, Config, Error, Output, OutputOption, PagingOption, Result, SearchEngine};
+use clap::{self, Parser, Subcommand};
+use directories::BaseDirs;
+use hors::{
+    self, Answers, CodeStyle, Config, Date, Error, ExportFormat, History, HistoryEntry, HistoryRef,
+    HyperlinkOption, LastQuery, Outcome, Output, OutputOption, PageCache, PagingOption,
+    SearchEngine, SearchFilter, SearchHit, Stash, StashedAnswer,
+};
 
 use reqwest::{Client, ClientBuilder};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
 
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::iter;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::result;
 use std::str::FromStr;
 
+/// Prompt of hors shell.
+const SHELL_PROMPT: &str = "hors> ";
+
 #[derive(Parser)]
 #[command(version = env!("CARGO_PKG_VERSION"), author = env!("CARGO_PKG_AUTHORS"), about = env!("CARGO_PKG_DESCRIPTION"))]
 struct Opts {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// just clear local hors cache.
     #[arg(long)]
     clear_cache: bool,
@@ -21,16 +38,42 @@ struct Opts {
     /// display only the answer link.
     #[arg(short, long)]
     link: bool,
+    /// pick search results and browse their answers interactively.
+    #[arg(short, long)]
+    interactive: bool,
     /// make raw output (not colorized)
     #[arg(short, long)]
     raw: bool,
-    /// "specify how to page output, can be `auto`, `never`"
+    /// display the question before answer.
+    #[arg(long)]
+    question: bool,
+    /// display comments of answer.
+    #[arg(long)]
+    comments: bool,
+    /// output answers as json.
+    #[arg(long)]
+    json: bool,
+    /// export answers as notes with attribution, can be `md`, `org`, `html`.
+    #[arg(long)]
+    export: Option<String>,
+    /// save answers to the given file instead of printing them, the note format is decided by
+    /// `--export` or file extension.
+    #[arg(long)]
+    save: Option<String>,
+    /// specify how to page output, can be `auto`, `always`, `never`.
+    /// The pager command can be customized through `$HORS_PAGER` or `$PAGER`.
     #[arg(
         short,
         long,
         default_value = "auto",
     )]
     paging: String,
+    /// show answers from the given position (starts from 1) of search results.
+    #[arg(long)]
+    pos: Option<usize>,
+    /// show answers of the given question link or id (like `so:12345`), without searching.
+    #[arg(long)]
+    url: Option<String>,
     /// number of answers to return.
     #[arg(
         short,
@@ -46,28 +89,103 @@ struct Opts {
         env = "HORS_ENGINE",
     )]
     engine: String,
+    /// make query from compiler or runtime error output, like `cargo build 2>&1 | hors --from-error`.
+    #[arg(long)]
+    from_error: bool,
+    /// show details of searching, like the query which is rewritten with project language.
+    #[arg(long)]
+    verbose: bool,
     /// Disable system proxy.
     #[arg(short, long)]
     disable_proxy: bool,
+    /// wrap answer text to the given width, `0` means don't wrap, terminal width is used by default.
+    #[arg(short, long)]
+    width: Option<usize>,
+    /// how to display code blocks in full answer, can be `plain`, `indent`, `box`.
+    #[arg(long, default_value = "indent")]
+    code_style: String,
+    /// display the code block at given index (starts from 1), rather than the most relevant one.
+    #[arg(long)]
+    code_index: Option<usize>,
+    /// display all code blocks in answer.
+    #[arg(long)]
+    all_code: bool,
+    /// clean up code (strip prompts and REPL output, dedent), so it can be pasted directly.
+    #[arg(long)]
+    clean: bool,
+    /// copy code snippet to clipboard (through OSC 52 terminal escape sequence).
+    #[arg(long)]
+    copy: bool,
+    /// show only answers of questions which have the tag, can be given many times.
+    #[arg(long)]
+    tag: Vec<String>,
+    /// show only answers whose vote score is at least the given score.
+    #[arg(long, allow_negative_numbers = true)]
+    min_score: Option<i32>,
+    /// show only answers which are posted on or after the date (yyyy-mm-dd).
+    #[arg(long)]
+    since: Option<String>,
+    /// show only answers which are posted on or before the date (yyyy-mm-dd).
+    #[arg(long)]
+    until: Option<String>,
+    /// show only accepted answers.
+    #[arg(long)]
+    accepted_only: bool,
+    /// display line numbers of code.
+    #[arg(long)]
+    line_numbers: bool,
+    /// don't display answer metadata (score, author, dates and question title).
+    #[arg(long)]
+    no_meta: bool,
+    /// make links clickable in terminal, can be `auto`, `always`, `never`.
+    #[arg(long, default_value = "auto")]
+    hyperlink: String,
+    /// banner line which is used to split answers.
+    #[arg(long, env = "HORS_SPLITTER")]
+    splitter: Option<String>,
+    /// query to search, it's read from stdin if it's `-` or stdin is piped.
     query: Vec<String>,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// manage stashed answers, which never expire.
+    Stash {
+        #[command(subcommand)]
+        action: StashAction,
+    },
+    /// show the following answers of the last query, without searching again.
+    Next,
+    /// start an interactive shell, each line is a query along with hors arguments.
+    Shell,
+    /// list query history, use `hors !N` to re-run the N-th query, or `hors !!` to re-run the last one.
+    History {
+        /// list only queries which contain the given keyword.
+        #[arg(long)]
+        grep: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum StashAction {
+    /// stash the last shown answer with the given name.
+    Save { name: String },
+    /// list stashed answers.
+    List,
+    /// view the stashed answer.
+    View { name: String },
+    /// remove the stashed answer.
+    Remove { name: String },
+    /// search stashed answers which contain the given keyword.
+    Search { keyword: Vec<String> },
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     #[cfg(windows)]
     let _ = ansi_term::enable_ansi_support();
     let opts: Opts = Opts::parse();
     env_logger::init();
-    if opts.clear_cache {
-        if let Err(e) = hors::clear_local_cache() {
-            eprintln!("clear local cache failed, reason: {:?}", e);
-            process::exit(1);
-        }
-        process::exit(0);
-    }
-
-    let search_engine = SearchEngine::from_str(&opts.engine)?;
-    debug!("Search under the {:?}", search_engine);
 
     // Initialize reqwest::Client instance.
     let mut client_builder: ClientBuilder = reqwest::ClientBuilder::new().cookie_store(true);
@@ -79,43 +197,397 @@ async fn main() -> Result<()> {
         eprintln!("Build client failed: {}", err);
         process::exit(1);
     });
+    let page_cache: PageCache = PageCache::load();
 
-    let target_links: Vec<String> =
-        hors::search_links_with_client(&opts.query.join(" "), search_engine, &client)
-            .await
-            .unwrap_or_else(|err| {
-                if let Error::Parse(_) = err {
-                    eprintln!(
-                        "Search stackoverflow link failed with '{:?}' search engine, \
-                         you can try another engine through `-e` argument, or specify `$HORS_ENGINE` env variable to another value", search_engine
-                    );
-                } else {
-                    eprintln!("Run query failed with '{:?}' search engine, error message: {}, \
-                    you can try another engine through `-e` argument, or specify `$HORS_ENGINE` env variable to another value", search_engine, err);
-                }
-                process::exit(1);
-            });
+    let result: result::Result<(), String> = match &opts.command {
+        Some(Command::Shell) => run_shell(&client, &page_cache).await,
+        _ => run(&opts, &client, &page_cache).await,
+    };
+    if let Err(msg) = result {
+        eprintln!("{}", msg);
+        process::exit(1);
+    }
+}
+
+/// Run hors with the given arguments.
+///
+/// # Returns
+///
+/// If it fails, return an error message which should be reported to user.
+async fn run(opts: &Opts, client: &Client, page_cache: &PageCache) -> result::Result<(), String> {
+    if opts.clear_cache {
+        return page_cache
+            .clear()
+            .map_err(|e| format!("clear local cache failed, reason: {:?}", e));
+    }
+    match &opts.command {
+        Some(Command::Stash { action }) => return run_stash(action, &opts.paging),
+        Some(Command::Shell) => return Err(String::from("Already in hors shell.")),
+        Some(Command::History { grep }) => return run_history(grep.as_deref()),
+        _ => {}
+    }
+    let filter: SearchFilter = search_filter(opts)?;
+
+    // `hors next` reuses links of the last query, and question which is given directly is
+    // shown without searching, so search engine is None and they're not recorded into history.
+    let (query, links, hits, position, engine): (
+        String,
+        Vec<String>,
+        Vec<SearchHit>,
+        usize,
+        Option<String>,
+    ) = match (&opts.command, direct_link(opts)?) {
+        (Some(Command::Next), _) => {
+            let last_query: LastQuery = LastQuery::load()
+                .map_err(|err| err.to_string())?
+                .ok_or("There is no previous query, please search something first.")?;
+            let position: usize = opts.pos.unwrap_or(last_query.next_position);
+            (
+                last_query.query,
+                last_query.links,
+                last_query.hits,
+                position,
+                None,
+            )
+        }
+        (_, Some(link)) => (link.clone(), vec![link], vec![], 1, None),
+        _ => {
+            let (query, engine): (String, String) = resolve_query(opts)?;
+            let (query, _) = hors::query::augment_with_project(&query);
+            if opts.verbose {
+                eprintln!("Query: {}", query);
+            }
+            let search_engine = SearchEngine::from_str(&engine).map_err(|err| err.to_string())?;
+            debug!("Search under the {:?}", search_engine);
+            let position: usize = opts.pos.unwrap_or(1);
+            // links before `position` are skipped, so they're counted in.
+            let numbers: usize = position.saturating_sub(1) + opts.number_answers as usize;
+            let hits: Vec<SearchHit> = search_hits(&query, search_engine, client, &filter, numbers)
+                .await
+                .inspect_err(|_| record_history(&query, Some(&engine), &[], Outcome::Failed))?;
+            let links: Vec<String> = hits.iter().map(|hit| hit.link.clone()).collect();
+            (query, links, hits, position, Some(engine))
+        }
+    };
+    let engine: Option<&str> = engine.as_deref();
+    let mut last_query =
+        LastQuery::new(query, links, position + opts.number_answers as usize).with_hits(hits);
+    let target_links: Vec<String> = last_query.links_from(position).to_vec();
+    if target_links.is_empty() {
+        record_history(&last_query.query, engine, &[], Outcome::NoResult);
+        return Err(format!("No more answers for '{}'.", last_query.query));
+    }
+    remember_query(&last_query);
+    let shown_links: &[String] =
+        &target_links[..target_links.len().min(opts.number_answers as usize)];
 
-    let conf: Config = init_config(&opts);
+    // project language is not a hint for question which is given directly.
+    let language: Option<&str> = match hors::query::question_link(&last_query.query) {
+        Some(_) => None,
+        None => hors::query::augment_with_project(&last_query.query).1,
+    };
+    let conf: Config = init_config(opts, &last_query.query)?
+        .with_language(language)
+        .with_filter(filter)
+        .with_search_hits(last_query.hits.clone());
     debug!("User config: {:?}", conf);
-    let answers: String = hors::get_answers_with_client(&target_links, conf, client)
+    if opts.interactive {
+        let loaded_links: Vec<String> = hors::interactive::run_interactive(
+            &last_query.query,
+            &target_links,
+            conf,
+            client.clone(),
+            page_cache.clone(),
+        )
         .await
-        .unwrap_or_else(|err| {
-            eprintln!("Hors is running to error: {}", err);
-            process::exit(1);
-        });
+        .map_err(|err| err.to_string())?;
+        // only questions which user loads are answered.
+        let outcome: Outcome = if loaded_links.is_empty() {
+            Outcome::Browsed
+        } else {
+            Outcome::Answered
+        };
+        record_history(&last_query.query, engine, &loaded_links, outcome);
+        return Ok(());
+    }
+    let answers: Answers = hors::collect_answers(&target_links, conf, client.clone(), page_cache)
+        .await
+        .map_err(|err| {
+            record_history(&last_query.query, engine, shown_links, Outcome::Failed);
+            format!("Hors is running to error: {}", err)
+        })?;
+    // links may be skipped to get enough answers, so continue from the last used link.
+    if answers.consumed != opts.number_answers as usize {
+        last_query.next_position = position + answers.consumed;
+        remember_query(&last_query);
+    }
+    record_history(
+        &last_query.query,
+        engine,
+        &answers.answered_links,
+        Outcome::Answered,
+    );
+    let answers: String = answers.output;
+    // remember the answer, so user can stash it later.
+    let last_answer = StashedAnswer::new(last_query.query, target_links, answers.clone());
+    if let Err(err) = last_answer.save_as_last() {
+        warn!("Can't remember the last answer, error msg: {:?}", err);
+    }
+    if let Some(path) = &opts.save {
+        fs::write(path, answers)
+            .map_err(|err| format!("Save answers to {} failed: {}", path, err))?;
+        println!("Answers are saved to {}", path);
+        return Ok(());
+    }
+    write_output(&answers, &opts.paging);
+    Ok(())
+}
+
+/// Run hors shell, each line is handled like the arguments of hors command.
+///
+/// Client and page cache are shared by all queries in shell, so cookies are kept and
+/// local cache file is loaded only once.
+async fn run_shell(client: &Client, page_cache: &PageCache) -> result::Result<(), String> {
+    let mut editor = DefaultEditor::new().map_err(|err| format!("Start shell failed: {}", err))?;
+    let history_path: Option<PathBuf> = shell_history_path();
+    if let Some(path) = &history_path {
+        // there is no history file when shell is run at the first time.
+        let _ = editor.load_history(path);
+    }
+
+    loop {
+        let line: String = match editor.readline(SHELL_PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(format!("Read line failed: {}", err)),
+        };
+        let line: &str = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "exit" || line == "quit" {
+            break;
+        }
+        let _ = editor.add_history_entry(line);
+
+        let args: Vec<String> = match shell_words::split(line) {
+            Ok(args) => args,
+            Err(err) => {
+                eprintln!("Invalid input: {}", err);
+                continue;
+            }
+        };
+        match Opts::try_parse_from(iter::once(String::from("hors")).chain(args)) {
+            Ok(opts) => {
+                if let Err(msg) = run(&opts, client, page_cache).await {
+                    eprintln!("{}", msg);
+                }
+            }
+            Err(err) => {
+                let _ = err.print();
+            }
+        }
+    }
+
+    if let Some(path) = &history_path {
+        if let Err(err) = editor.save_history(path) {
+            warn!("Can't save shell history, error msg: {:?}", err);
+        }
+    }
+    Ok(())
+}
+
+/// Get path of shell history file, like `$HOME/.cache/hors/shell_history` on linux.
+fn shell_history_path() -> Option<PathBuf> {
+    let mut path: PathBuf = BaseDirs::new()?.cache_dir().to_path_buf();
+    path.push("hors");
+    fs::create_dir_all(&path).ok()?;
+    path.push("shell_history");
+    Some(path)
+}
+
+/// Get query and search engine from arguments.
+///
+/// `!N` and `!!` re-run the N-th and the last query in history with the same search engine.
+fn resolve_query(opts: &Opts) -> result::Result<(String, String), String> {
+    let mut query: String = opts.query.join(" ");
+    let reference: HistoryRef = match HistoryRef::parse(&query) {
+        Some(reference) => reference,
+        None => {
+            if query == "-" || (query.is_empty() && !io::stdin().is_terminal()) {
+                query = read_stdin()?;
+            }
+            if opts.from_error {
+                query =
+                    hors::query_from_error(&query).ok_or("Can't find error message from input.")?;
+                // show the query, so user knows what is searched.
+                eprintln!("{}", query);
+            } else {
+                query = query.split_whitespace().collect::<Vec<&str>>().join(" ");
+            }
+            return Ok((query, opts.engine.clone()));
+        }
+    };
+    let history: History = History::load().map_err(|err| err.to_string())?;
+    let entry: &HistoryEntry = history
+        .get_by_ref(reference)
+        .ok_or_else(|| format!("No query '{}' in history.", query))?;
+    // echo the query which is re-run, like shell does.
+    eprintln!("{}", entry.query);
+    Ok((entry.query.clone(), entry.engine.clone()))
+}
+
+/// Get question link which is given by `--url`, or given as query like `so:12345`.
+fn direct_link(opts: &Opts) -> result::Result<Option<String>, String> {
+    if let Some(url) = &opts.url {
+        return hors::query::question_link(url)
+            .map(Some)
+            .ok_or_else(|| format!("'{}' is not a question link or id.", url));
+    }
+    match opts.query.as_slice() {
+        [input] => Ok(hors::query::question_link(input)),
+        _ => Ok(None),
+    }
+}
+
+/// Read all input from stdin, it fails if the input is blank.
+fn read_stdin() -> result::Result<String, String> {
+    let mut input: String = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|err| format!("Read query from stdin failed: {}", err))?;
+    if input.trim().is_empty() {
+        return Err(String::from("Query from stdin is empty."));
+    }
+    Ok(input)
+}
+
+/// Record query into history, nothing is recorded if search engine is None.
+fn record_history(query: &str, engine: Option<&str>, links: &[String], outcome: Outcome) {
+    let engine: &str = match engine {
+        Some(engine) => engine,
+        None => return,
+    };
+    let entry = HistoryEntry::new(
+        query.to_string(),
+        engine.to_string(),
+        links.to_vec(),
+        outcome,
+    );
+    if let Err(err) = History::append(&entry) {
+        warn!("Can't record query into history, error msg: {:?}", err);
+    }
+}
+
+/// List query history, only queries which contain `grep` are listed if it's given.
+fn run_history(grep: Option<&str>) -> result::Result<(), String> {
+    let history: History = History::load().map_err(|err| err.to_string())?;
+    for (number, entry) in history.iter() {
+        if let Some(keyword) = grep {
+            if !entry.contains(keyword) {
+                continue;
+            }
+        }
+        println!(
+            "{:>5}  {}  {:<13} {} ({})",
+            number,
+            entry.format_time(),
+            entry.engine,
+            entry.query,
+            entry.outcome.as_str()
+        );
+        for link in entry.links.iter() {
+            println!("       {}", link);
+        }
+    }
+    Ok(())
+}
+
+/// Save the last query, so `hors next` can continue from it.
+fn remember_query(last_query: &LastQuery) {
+    if let Err(err) = last_query.save() {
+        warn!("Can't remember the last query, error msg: {:?}", err);
+    }
+}
+
+/// Search links according to query, along with their titles and snippets.
+async fn search_hits(
+    query: &str,
+    search_engine: SearchEngine,
+    client: &Client,
+    filter: &SearchFilter,
+    numbers: usize,
+) -> result::Result<Vec<SearchHit>, String> {
+    hors::search_hits(query, search_engine, client, filter, numbers)
+        .await
+        .map_err(|err| {
+            if let Error::Parse(_) = err {
+                format!(
+                    "Search stackoverflow link failed with '{:?}' search engine, \
+                     you can try another engine through `-e` argument, or specify `$HORS_ENGINE` env variable to another value", search_engine
+                )
+            } else {
+                format!("Run query failed with '{:?}' search engine, error message: {}, \
+                you can try another engine through `-e` argument, or specify `$HORS_ENGINE` env variable to another value", search_engine, err)
+            }
+        })
+}
+
+/// Write answers to terminal, they're paged according to the paging option.
+fn write_output(answers: &str, paging: &str) {
     let answers: String = format!("\n\n{}", answers);
 
     // create an output object and get an output handler, use the handler to handle our result.
-    let paging_option = PagingOption::from_str(&opts.paging).unwrap_or(PagingOption::Auto);
+    let paging_option = PagingOption::from_str(paging).unwrap_or(PagingOption::Auto);
     let mut output = Output::new(&paging_option);
     let handler = output.get_handler();
     handler.write_all(answers.as_bytes()).expect("success");
+}
+
+/// Manage stashed answers.
+fn run_stash(action: &StashAction, paging: &str) -> result::Result<(), String> {
+    let mut stash: Stash = Stash::load().map_err(|err| err.to_string())?;
+    match action {
+        StashAction::Save { name } => {
+            let answer: StashedAnswer = StashedAnswer::load_last()
+                .map_err(|err| err.to_string())?
+                .ok_or("There is no answer to stash, please search something first.")?;
+            if stash.put(name.clone(), answer).is_some() {
+                println!("Stashed answer '{}' is replaced.", name);
+            } else {
+                println!("Answer is stashed as '{}'.", name);
+            }
+            stash.save().map_err(|err| err.to_string())?;
+        }
+        StashAction::List => {
+            for (name, answer) in stash.iter() {
+                println!("{}\t{}", name, answer.query);
+            }
+        }
+        StashAction::View { name } => match stash.get(name) {
+            Some(answer) => write_output(&answer.content, paging),
+            None => return Err(format!("No stashed answer named '{}'.", name)),
+        },
+        StashAction::Remove { name } => {
+            if stash.remove(name).is_none() {
+                return Err(format!("No stashed answer named '{}'.", name));
+            }
+            stash.save().map_err(|err| err.to_string())?;
+            println!("Stashed answer '{}' is removed.", name);
+        }
+        StashAction::Search { keyword } => {
+            let keyword: String = keyword.join(" ");
+            for (name, answer) in stash.search(&keyword) {
+                println!("{}\t{}", name, answer.query);
+            }
+        }
+    }
     Ok(())
 }
 
 /// initialize config from user input arguments.
-fn init_config(opts: &Opts) -> Config {
+fn init_config(opts: &Opts, query: &str) -> result::Result<Config, String> {
     let output_option = if opts.link {
         OutputOption::Links
     } else if opts.all {
@@ -124,5 +596,72 @@ fn init_config(opts: &Opts) -> Config {
         OutputOption::OnlyCode
     };
 
-    Config::new(output_option, opts.number_answers, !opts.raw)
+    let code_style = CodeStyle::from_str(&opts.code_style).map_err(|err| err.to_string())?;
+    let hyperlink = HyperlinkOption::from_str(&opts.hyperlink).map_err(|err| err.to_string())?;
+    let export = export_format(opts)?;
+    let colorize = !opts.raw && !opts.json && export.is_none();
+
+    Ok(Config::new(output_option, opts.number_answers, colorize)
+        .with_width(opts.width)
+        .with_code_style(code_style)
+        .with_line_numbers(opts.line_numbers)
+        .with_splitter(opts.splitter.clone())
+        .with_hyperlink(hyperlink)
+        .with_meta(!opts.no_meta)
+        .with_question(opts.question)
+        .with_comments(opts.comments)
+        .with_json(opts.json)
+        .with_query(query)
+        .with_code_index(opts.code_index)
+        .with_all_code(opts.all_code)
+        .with_clean(opts.clean)
+        .with_copy(opts.copy)
+        .with_export(export))
+}
+
+/// Make filter of search results from user input arguments.
+fn search_filter(opts: &Opts) -> result::Result<SearchFilter, String> {
+    let parse_date = |date: &Option<String>| -> result::Result<Option<Date>, String> {
+        date.as_deref()
+            .map(|date| {
+                Date::from_str(date).map_err(|err| format!("Invalid date '{}': {}", date, err))
+            })
+            .transpose()
+    };
+    Ok(SearchFilter {
+        // tags can be separated by comma too, like `--tag rust,serde`.
+        tags: opts
+            .tag
+            .iter()
+            .flat_map(|tag| tag.split(','))
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        min_score: opts.min_score,
+        since: parse_date(&opts.since)?,
+        until: parse_date(&opts.until)?,
+        accepted_only: opts.accepted_only,
+    })
+}
+
+/// Get note format to export answers.
+///
+/// If user saves answers without `--export`, the format is decided by file extension,
+/// and markdown is used by default.
+fn export_format(opts: &Opts) -> result::Result<Option<ExportFormat>, String> {
+    if let Some(export) = &opts.export {
+        return ExportFormat::from_str(export)
+            .map(Some)
+            .map_err(|err| format!("Invalid export format '{}': {}", export, err));
+    }
+    let path: &str = match opts.save.as_deref() {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let format = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(|extension| ExportFormat::from_str(extension).ok())
+        .unwrap_or(ExportFormat::Markdown);
+    Ok(Some(format))
 }
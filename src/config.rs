@@ -1,4 +1,7 @@
+use crate::answer::{is_hyperlink_terminal, SPLITTER};
+use crate::engine::SearchHit;
 use crate::error::{Error, Result};
+use crate::search_config::SEARCH_CONFIG;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy)]
@@ -25,7 +28,40 @@ pub enum SearchEngine {
     StackOverflow,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// How code blocks are displayed along with answer text.
+pub enum CodeStyle {
+    /// Output code as it is.
+    Plain,
+    /// Indent code, and put a language label above it.
+    Indent,
+    /// Draw a box around code, along with a language label.
+    Box,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Indicate that links should be clickable in terminal or not.
+pub enum HyperlinkOption {
+    /// Make links clickable when output is colorized and terminal supports it.
+    Auto,
+    /// Always make links clickable.
+    Always,
+    /// Never make links clickable.
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Note formats which answers can be exported to.
+pub enum ExportFormat {
+    /// Markdown note.
+    Markdown,
+    /// Emacs org-mode note.
+    Org,
+    /// Html page.
+    Html,
+}
+
+#[derive(Debug, Clone)]
 /// The user config information is integrated here.
 pub struct Config {
     /// Terminal output options.
@@ -34,6 +70,42 @@ pub struct Config {
     numbers: u8,
     /// Indicate that the output code shoule be colorized or not.
     colorize: bool,
+    /// The width to wrap answer text, `None` means using terminal width.
+    width: Option<usize>,
+    /// How code blocks are displayed in detailed answer.
+    code_style: CodeStyle,
+    /// Indicate that code lines should be numbered or not.
+    line_numbers: bool,
+    /// Banner line which is used to split answers.
+    splitter: Option<String>,
+    /// Make links clickable in terminal or not.
+    hyperlink: HyperlinkOption,
+    /// Indicate that answer metadata (score, author, etc) should be output or not.
+    meta: bool,
+    /// Indicate that question body should be output before answer or not.
+    question: bool,
+    /// Indicate that comments of answer should be output or not.
+    comments: bool,
+    /// Output answers as json rather than text.
+    json: bool,
+    /// User input query, which is used to select the most relevant code block.
+    query: String,
+    /// Index of code block (starts from 1) to output, `None` means the most relevant one.
+    code_index: Option<usize>,
+    /// Output all code blocks in answer.
+    all_code: bool,
+    /// Clean up code snippet (strip prompts, dedent, etc) so it can be pasted directly.
+    clean: bool,
+    /// Copy code snippet to clipboard.
+    copy: bool,
+    /// Export answers as notes with the given format.
+    export: Option<ExportFormat>,
+    /// Language of user's project, which is preferred to guess syntax of code.
+    language: Option<String>,
+    /// Filters of questions and answers.
+    filter: SearchFilter,
+    /// Search hits, which provide titles and snippets of links.
+    search_hits: Vec<SearchHit>,
 }
 
 impl Config {
@@ -42,9 +114,132 @@ impl Config {
             option: output_option,
             numbers,
             colorize,
+            width: None,
+            code_style: CodeStyle::Indent,
+            line_numbers: false,
+            splitter: None,
+            hyperlink: HyperlinkOption::Never,
+            meta: false,
+            question: false,
+            comments: false,
+            json: false,
+            query: String::new(),
+            code_index: None,
+            all_code: false,
+            clean: false,
+            copy: false,
+            export: None,
+            language: None,
+            filter: SearchFilter::default(),
+            search_hits: Vec::new(),
         }
     }
 
+    /// Wrap answer text with given width, `0` means don't wrap.
+    pub fn with_width(mut self, width: Option<usize>) -> Config {
+        self.width = width;
+        self
+    }
+
+    pub fn with_code_style(mut self, code_style: CodeStyle) -> Config {
+        self.code_style = code_style;
+        self
+    }
+
+    pub fn with_line_numbers(mut self, line_numbers: bool) -> Config {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// Use the given banner line to split answers.
+    pub fn with_splitter(mut self, splitter: Option<String>) -> Config {
+        self.splitter = splitter;
+        self
+    }
+
+    pub fn with_hyperlink(mut self, hyperlink: HyperlinkOption) -> Config {
+        self.hyperlink = hyperlink;
+        self
+    }
+
+    /// Output answer metadata (score, author, dates, question title) along with answer.
+    pub fn with_meta(mut self, meta: bool) -> Config {
+        self.meta = meta;
+        self
+    }
+
+    /// Output question body before answer.
+    pub fn with_question(mut self, question: bool) -> Config {
+        self.question = question;
+        self
+    }
+
+    /// Output comments of answer.
+    pub fn with_comments(mut self, comments: bool) -> Config {
+        self.comments = comments;
+        self
+    }
+
+    /// Output answers as json, so other programs can consume them.
+    pub fn with_json(mut self, json: bool) -> Config {
+        self.json = json;
+        self
+    }
+
+    /// Set user input query, code blocks which are relative to query are preferred.
+    pub fn with_query(mut self, query: &str) -> Config {
+        self.query = query.to_string();
+        self
+    }
+
+    /// Output the code block at given index (starts from 1), rather than the most relevant one.
+    pub fn with_code_index(mut self, code_index: Option<usize>) -> Config {
+        self.code_index = code_index;
+        self
+    }
+
+    /// Output all code blocks in answer.
+    pub fn with_all_code(mut self, all_code: bool) -> Config {
+        self.all_code = all_code;
+        self
+    }
+
+    /// Clean up code snippet, prompts and REPL output are removed, indentation is normalized.
+    pub fn with_clean(mut self, clean: bool) -> Config {
+        self.clean = clean;
+        self
+    }
+
+    /// Copy code snippet (not colorized) to clipboard through OSC 52 escape sequence.
+    pub fn with_copy(mut self, copy: bool) -> Config {
+        self.copy = copy;
+        self
+    }
+
+    /// Export answers as notes, along with question title, author, link and license attribution.
+    pub fn with_export(mut self, export: Option<ExportFormat>) -> Config {
+        self.export = export;
+        self
+    }
+
+    /// Prefer the given language (like `rust`) to guess syntax of code.
+    pub fn with_language(mut self, language: Option<&str>) -> Config {
+        self.language = language.map(String::from);
+        self
+    }
+
+    /// Skip questions and answers which don't match the filter.
+    pub fn with_filter(mut self, filter: SearchFilter) -> Config {
+        self.filter = filter;
+        self
+    }
+
+    /// Use titles and snippets of search hits when links are displayed.
+    pub fn with_search_hits(mut self, search_hits: Vec<SearchHit>) -> Config {
+        self.search_hits = search_hits;
+        self
+    }
+
     pub fn option(&self) -> &OutputOption {
         &self.option
     }
@@ -56,6 +251,106 @@ impl Config {
     pub fn colorize(&self) -> bool {
         self.colorize
     }
+
+    /// Get the width to wrap answer text.
+    ///
+    /// # Returns
+    ///
+    /// The user given width, or the terminal width if user doesn't give it.  Returns None
+    /// if answer text shouldn't be wrapped.
+    pub fn width(&self) -> Option<usize> {
+        match self.width {
+            Some(0) => None,
+            Some(width) => Some(width),
+            None => terminal_size::terminal_size().map(|(width, _)| width.0 as usize),
+        }
+    }
+
+    pub fn code_style(&self) -> CodeStyle {
+        self.code_style
+    }
+
+    pub fn line_numbers(&self) -> bool {
+        self.line_numbers
+    }
+
+    /// Return true if links should be output as terminal hyperlinks.
+    pub fn hyperlink(&self) -> bool {
+        match self.hyperlink {
+            HyperlinkOption::Always => true,
+            HyperlinkOption::Never => false,
+            HyperlinkOption::Auto => self.colorize && is_hyperlink_terminal(),
+        }
+    }
+
+    pub fn meta(&self) -> bool {
+        self.meta
+    }
+
+    pub fn question(&self) -> bool {
+        self.question
+    }
+
+    pub fn comments(&self) -> bool {
+        self.comments
+    }
+
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn code_index(&self) -> Option<usize> {
+        self.code_index
+    }
+
+    pub fn all_code(&self) -> bool {
+        self.all_code
+    }
+
+    pub fn clean(&self) -> bool {
+        self.clean
+    }
+
+    pub fn copy(&self) -> bool {
+        self.copy
+    }
+
+    pub fn export(&self) -> Option<ExportFormat> {
+        self.export
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    pub fn filter(&self) -> &SearchFilter {
+        &self.filter
+    }
+
+    pub fn search_hits(&self) -> &[SearchHit] {
+        &self.search_hits
+    }
+
+    /// Get search hit of the given link.
+    pub fn search_hit(&self, link: &str) -> Option<&SearchHit> {
+        self.search_hits.iter().find(|hit| hit.link == link)
+    }
+
+    /// Get splitter between answers, the banner line can be given by user or config file.
+    pub fn splitter(&self) -> String {
+        match self
+            .splitter
+            .as_deref()
+            .or_else(|| SEARCH_CONFIG.get_splitter())
+        {
+            Some(banner) => format!("\n{}\n\n", banner),
+            None => SPLITTER.to_string(),
+        }
+    }
 }
 
 impl FromStr for SearchEngine {
@@ -72,9 +367,132 @@ impl FromStr for SearchEngine {
     }
 }
 
+impl FromStr for CodeStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "plain" => Ok(CodeStyle::Plain),
+            "indent" => Ok(CodeStyle::Indent),
+            "box" => Ok(CodeStyle::Box),
+            _ => Err(Error::from_parse("Not supported code style")),
+        }
+    }
+}
+
+impl FromStr for HyperlinkOption {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(HyperlinkOption::Auto),
+            "always" => Ok(HyperlinkOption::Always),
+            "never" => Ok(HyperlinkOption::Never),
+            _ => Err(Error::from_parse("Not supported hyperlink option")),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "org" => Ok(ExportFormat::Org),
+            "html" => Ok(ExportFormat::Html),
+            _ => Err(Error::from_parse("Not supported export format")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+/// A date which is formatted as `yyyy-mm-dd`.
+pub struct Date {
+    year: i64,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Get unix timestamp of the beginning of the date (in UTC).
+    pub fn timestamp(&self) -> i64 {
+        // The algorithm comes from http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year: i64 = if self.month <= 2 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let era: i64 = if year >= 0 { year } else { year - 399 } / 400;
+        let yoe: i64 = year - era * 400;
+        let month: i64 = self.month as i64;
+        let doy: i64 =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe: i64 = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        (era * 146097 + doe - 719468) * 86400
+    }
+}
+
+/// Get how many days the month has.
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl FromStr for Date {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.trim().split('-').collect();
+        let (year, month, day) = match parts.as_slice() {
+            [year, month, day] => (year.parse(), month.parse(), day.parse()),
+            _ => return Err(Error::from_parse("Date should be formatted as yyyy-mm-dd")),
+        };
+        match (year, month, day) {
+            (Ok(year), Ok(month @ 1..=12), Ok(day))
+                if day >= 1 && day <= days_in_month(year, month) =>
+            {
+                Ok(Date { year, month, day })
+            }
+            _ => Err(Error::from_parse("Date should be formatted as yyyy-mm-dd")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// Filters of search results, questions and answers which don't match them are skipped.
+pub struct SearchFilter {
+    /// Question should have all of these tags.
+    pub tags: Vec<String>,
+    /// Minimum vote score of answer.
+    pub min_score: Option<i32>,
+    /// Answer should be posted on or after the date.
+    pub since: Option<Date>,
+    /// Answer should be posted on or before the date.
+    pub until: Option<Date>,
+    /// Only accepted answer is shown.
+    pub accepted_only: bool,
+}
+
+impl SearchFilter {
+    /// Return true if no filter is given.
+    pub fn is_empty(&self) -> bool {
+        *self == SearchFilter::default()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
+/// How the final result should be paged.
 pub enum PagingOption {
+    /// Page output only when it's written to a terminal.
     Auto,
+    /// Always page output, even if stdout is not a terminal.
+    Always,
+    /// Never page output, just dump it to stdout.
     Never,
 }
 
@@ -84,6 +502,7 @@ impl FromStr for PagingOption {
     fn from_str(s: &str) -> Result<Self> {
         match s {
             "auto" => Ok(PagingOption::Auto),
+            "always" => Ok(PagingOption::Always),
             "never" => Ok(PagingOption::Never),
             _ => Err(Error::from_parse("Not supported paging option")),
         }
@@ -97,28 +516,123 @@ mod tests {
     #[test]
     fn test_search_engine_from_str() {
         let search_engine = SearchEngine::from_str("bing");
-        assert_eq!(search_engine.is_ok(), true);
+        assert!(search_engine.is_ok());
         let search_engine = SearchEngine::from_str("google");
-        assert_eq!(search_engine.is_ok(), true);
+        assert!(search_engine.is_ok());
     }
 
     #[test]
     fn test_search_engine_from_invalid_str() {
         let search_engine = SearchEngine::from_str("what's this?");
-        assert_eq!(search_engine.is_err(), true);
+        assert!(search_engine.is_err());
     }
 
     #[test]
     fn test_paging_option_from_str() {
         let paging_option = PagingOption::from_str("auto");
-        assert_eq!(paging_option.is_ok(), true);
+        assert!(paging_option.is_ok());
         let paging_option = PagingOption::from_str("never");
-        assert_eq!(paging_option.is_ok(), true);
+        assert!(paging_option.is_ok());
+        let paging_option = PagingOption::from_str("always");
+        assert!(paging_option.is_ok());
+    }
+
+    #[test]
+    fn test_code_style_from_str() {
+        assert_eq!(CodeStyle::from_str("plain").unwrap(), CodeStyle::Plain);
+        assert_eq!(CodeStyle::from_str("indent").unwrap(), CodeStyle::Indent);
+        assert_eq!(CodeStyle::from_str("box").unwrap(), CodeStyle::Box);
+        assert!(CodeStyle::from_str("invalid").is_err());
+        assert!(CodeStyle::from_str("fenced").is_err());
+    }
+
+    #[test]
+    fn test_default_code_style_is_indent() {
+        let conf = Config::new(OutputOption::All, 1, false);
+        assert_eq!(conf.code_style(), CodeStyle::Indent);
+    }
+
+    #[test]
+    fn test_hyperlink_option_from_str() {
+        assert_eq!(
+            HyperlinkOption::from_str("auto").unwrap(),
+            HyperlinkOption::Auto
+        );
+        assert_eq!(
+            HyperlinkOption::from_str("always").unwrap(),
+            HyperlinkOption::Always
+        );
+        assert_eq!(
+            HyperlinkOption::from_str("never").unwrap(),
+            HyperlinkOption::Never
+        );
+        assert!(HyperlinkOption::from_str("invalid").is_err());
+    }
+
+    #[test]
+    fn test_export_format_from_str() {
+        assert_eq!(
+            ExportFormat::from_str("md").unwrap(),
+            ExportFormat::Markdown
+        );
+        assert_eq!(
+            ExportFormat::from_str("markdown").unwrap(),
+            ExportFormat::Markdown
+        );
+        assert_eq!(ExportFormat::from_str("org").unwrap(), ExportFormat::Org);
+        assert_eq!(ExportFormat::from_str("html").unwrap(), ExportFormat::Html);
+        assert!(ExportFormat::from_str("pdf").is_err());
+    }
+
+    #[test]
+    fn test_date_from_str() {
+        let date: Date = Date::from_str("2024-02-29").unwrap();
+        assert_eq!(date.timestamp(), 1_709_164_800);
+        assert_eq!(Date::from_str("1970-01-01").unwrap().timestamp(), 0);
+        assert!(Date::from_str("2023-01-02").unwrap() < Date::from_str("2023-02-01").unwrap());
+        assert!(Date::from_str("2024-13-01").is_err());
+        assert!(Date::from_str("2023-02-31").is_err());
+        assert!(Date::from_str("2023-04-31").is_err());
+        assert!(Date::from_str("2023-02-29").is_err());
+        assert!(Date::from_str("1900-02-29").is_err());
+        assert!(Date::from_str("2000-02-29").is_ok());
+        assert!(Date::from_str("2023-12-00").is_err());
+        assert!(Date::from_str("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_search_filter_is_empty() {
+        let mut filter: SearchFilter = SearchFilter::default();
+        assert!(filter.is_empty());
+        filter.accepted_only = true;
+        assert!(!filter.is_empty());
+    }
+
+    #[test]
+    fn test_config_hyperlink() {
+        let conf = Config::new(OutputOption::All, 1, true);
+        assert!(!conf.hyperlink());
+        let conf = Config::new(OutputOption::All, 1, false).with_hyperlink(HyperlinkOption::Always);
+        assert!(conf.hyperlink());
+    }
+
+    #[test]
+    fn test_config_width_when_wrap_is_disabled() {
+        let conf = Config::new(OutputOption::All, 1, false).with_width(Some(0));
+        assert_eq!(conf.width(), None);
+        let conf = Config::new(OutputOption::All, 1, false).with_width(Some(60));
+        assert_eq!(conf.width(), Some(60));
+    }
+
+    #[test]
+    fn test_config_splitter() {
+        let conf = Config::new(OutputOption::All, 1, false).with_splitter(Some("-----".into()));
+        assert_eq!(conf.splitter(), "\n-----\n\n");
     }
 
     #[test]
     fn test_invalid_paging_option() {
         let paging_option = PagingOption::from_str("invalid");
-        assert_eq!(paging_option.is_err(), true);
+        assert!(paging_option.is_err());
     }
 }
@@ -1,42 +1,58 @@
-use super::Engine;
+use super::{query_string, Engine, SearchHit};
+use crate::link::QuestionLink;
 use crate::search_config::SEARCH_CONFIG;
 use select::document::Document;
+use select::node::Node;
 use select::predicate::{Class, Name, Predicate};
 
+/// Bing shows 10 results in a page.
+const RESULTS_PER_PAGE: usize = 10;
+
 pub struct Bing;
 
 impl Engine for Bing {
-    fn get_query_url(&self, query: &str, use_https: bool) -> String {
-        if use_https {
-            format!(
-                "https://{}/search?q=site:stackoverflow.com%20{}&hl=en",
-                SEARCH_CONFIG.get_bing_domain(),
-                query
-            )
-        } else {
-            format!(
-                "http://{}/search?q=site:stackoverflow.com%20{}&hl=en",
-                SEARCH_CONFIG.get_bing_domain(),
-                query
-            )
+    fn get_page_url(&self, query: &str, page: usize, use_https: bool) -> String {
+        let scheme = if use_https { "https" } else { "http" };
+        let query: String = format!("site:stackoverflow.com {}", query);
+        // `first` is the position (starts from 1) of the first result in page.
+        let first: String = (page * RESULTS_PER_PAGE + 1).to_string();
+        let mut params: Vec<(&str, &str)> = vec![("q", query.as_str()), ("hl", "en")];
+        if page > 0 {
+            params.push(("first", first.as_str()));
         }
+        format!(
+            "{}://{}/search?{}",
+            scheme,
+            SEARCH_CONFIG.get_bing_domain(),
+            query_string(params)
+        )
     }
 
-    fn extract_links(&self, page: &str) -> Option<Vec<String>> {
+    fn extract_links(&self, page: &str) -> Option<Vec<SearchHit>> {
         let doc: Document = Document::from(page);
-        let target_elements =
-            doc.find(Class("b_algo").descendant(Name("h2")).descendant(Name("a")));
-        let links: Vec<String> = target_elements
-            .filter_map(|node| node.attr("href"))
-            .filter(|link| link.contains("stackoverflow.com"))
-            .map(String::from)
+        let hits: Vec<SearchHit> = doc
+            .find(Class("b_algo"))
+            .enumerate()
+            .filter_map(|(index, result)| {
+                let anchor: Node = result.find(Name("h2").descendant(Name("a"))).next()?;
+                let link: QuestionLink = QuestionLink::parse(anchor.attr("href")?)?;
+                let snippet: Option<String> = result
+                    .find(Class("b_caption").descendant(Name("p")))
+                    .next()
+                    .map(|node| node.text());
+                Some(
+                    SearchHit::new(&link, index + 1)
+                        .with_title(Some(anchor.text()))
+                        .with_snippet(snippet),
+                )
+            })
             .collect();
 
-        debug!("Links extract from bing: {:?}", links);
-        if links.is_empty() {
+        debug!("Links extract from bing: {:?}", hits);
+        if hits.is_empty() {
             return None;
         }
-        Some(links)
+        Some(hits)
     }
 }
 
@@ -58,18 +74,32 @@ mod tests {
             <h2><a target="_blank" href="https://stackoverflow.com"></a></h2>
         </li>
         <li class="b_algo">
-            <h2><a target="_blank" href="https://stackoverflow.com/aa"></a></h2>
+            <h2><a target="_blank" href="https://stackoverflow.com/questions/12/aa">How to <strong>parse</strong> json in C#?</a></h2>
+            <div class="b_caption"><p>Use  System.Text.Json ...</p></div>
+        </li>
+        <li class="b_algo">
+            <h2><a target="_blank" href="https://stackoverflow.com/a/34"></a></h2>
         </li>
     </body>
 </html>"#,
         );
-        let possible_links: Option<Vec<String>> = engine.extract_links(&page);
-        assert_eq!(possible_links.is_some(), true);
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(&page);
+        assert!(possible_links.is_some());
         assert_eq!(
             possible_links.unwrap(),
             vec![
-                String::from("https://stackoverflow.com"),
-                String::from("https://stackoverflow.com/aa")
+                SearchHit {
+                    link: String::from("https://stackoverflow.com/questions/12/aa"),
+                    title: Some(String::from("How to parse json in C#?")),
+                    snippet: Some(String::from("Use System.Text.Json ...")),
+                    rank: 3,
+                },
+                SearchHit {
+                    link: String::from("https://stackoverflow.com/a/34"),
+                    title: None,
+                    snippet: None,
+                    rank: 4,
+                }
             ]
         )
     }
@@ -78,8 +108,8 @@ mod tests {
     fn test_extract_links_when_there_are_no_links_available() {
         let page: String = String::from("<html></html>");
         let engine = Bing;
-        let possible_links: Option<Vec<String>> = engine.extract_links(&page);
-        assert_eq!(possible_links.is_none(), true);
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(&page);
+        assert!(possible_links.is_none());
     }
 
     #[test]
@@ -87,7 +117,27 @@ mod tests {
         let engine = Bing;
         let result: String = engine.get_query_url(&String::from("how to write unit test"), true);
         assert_eq!(
-            "https://www.bing.com/search?q=site:stackoverflow.com%20how to write unit test&hl=en",
+            "https://www.bing.com/search?q=site%3Astackoverflow.com+how+to+write+unit+test&hl=en",
+            result
+        );
+    }
+
+    #[test]
+    fn test_get_query_url_with_special_characters() {
+        let engine = Bing;
+        let result: String = engine.get_query_url("c# async & c++", true);
+        assert_eq!(
+            "https://www.bing.com/search?q=site%3Astackoverflow.com+c%23+async+%26+c%2B%2B&hl=en",
+            result
+        );
+    }
+
+    #[test]
+    fn test_get_page_url() {
+        let engine = Bing;
+        let result: String = engine.get_page_url("parse json", 2, true);
+        assert_eq!(
+            "https://www.bing.com/search?q=site%3Astackoverflow.com+parse+json&hl=en&first=21",
             result
         );
     }
@@ -97,7 +147,7 @@ mod tests {
         let engine = Bing;
         let result: String = engine.get_query_url(&String::from("how to write unit test"), false);
         assert_eq!(
-            "http://www.bing.com/search?q=site:stackoverflow.com%20how to write unit test&hl=en",
+            "http://www.bing.com/search?q=site%3Astackoverflow.com+how+to+write+unit+test&hl=en",
             result
         );
     }
@@ -1,36 +1,43 @@
-use super::Engine;
+use super::{query_string, Engine, SearchHit};
+use crate::link::QuestionLink;
 use crate::search_config::SEARCH_CONFIG;
 use select::document::Document;
+use select::node::Node;
 use select::predicate::Class;
 use url::form_urlencoded;
 
+/// The html version of DuckDuckGo shows 30 results in a page.
+const RESULTS_PER_PAGE: usize = 30;
+
 pub struct DuckDuckGo;
 
 impl Engine for DuckDuckGo {
-    fn get_query_url(&self, query: &str, use_https: bool) -> String {
+    fn get_page_url(&self, query: &str, page: usize, use_https: bool) -> String {
         // For more information about query url, the information here is useful:
         // https://stackoverflow.com/questions/37012469/duckduckgo-api-getting-search-results
-        if use_https {
-            format!(
-                "https://{}/html?q=site:stackoverflow.com%20{}&t=hj&ia=web",
-                SEARCH_CONFIG.get_ddg_domain(),
-                query
-            )
-        } else {
-            format!(
-                "http://{}/html?q=site:stackoverflow.com%20{}&t=hj&ia=web",
-                SEARCH_CONFIG.get_ddg_domain(),
-                query
-            )
+        let scheme = if use_https { "https" } else { "http" };
+        let query: String = format!("site:stackoverflow.com {}", query);
+        // `s` is the offset of the first result in page.
+        let offset: String = (page * RESULTS_PER_PAGE).to_string();
+        let mut params: Vec<(&str, &str)> = vec![("q", query.as_str()), ("t", "hj"), ("ia", "web")];
+        if page > 0 {
+            params.push(("s", offset.as_str()));
         }
+        format!(
+            "{}://{}/html?{}",
+            scheme,
+            SEARCH_CONFIG.get_ddg_domain(),
+            query_string(params)
+        )
     }
 
-    fn extract_links(&self, page: &str) -> Option<Vec<String>> {
+    fn extract_links(&self, page: &str) -> Option<Vec<SearchHit>> {
         let doc: Document = Document::from(page);
         let target_elements = doc.find(Class("result__a"));
-        let links: Vec<String> = target_elements
-            .filter_map(|node| node.attr("href"))
-            .filter_map(|link| {
+        let hits: Vec<SearchHit> = target_elements
+            .enumerate()
+            .filter_map(|(index, node)| {
+                let link: &str = node.attr("href")?;
                 // The link may hide in uddg attribute.
                 // e.g: /l/?kh=-1&uddg=https%3A%2F%2Fdoc.rust%2Dlang.org%2Fstd%2Fprimitive.str.html
                 // So try to find uddg parameter first.
@@ -38,20 +45,41 @@ impl Engine for DuckDuckGo {
                     .find(|(k, _)| k == "uddg")
                     .map(|(_, v)| v.into_owned());
                 // If we can't find redirect link in uddg, just return link.
-                redirect_link.or_else(|| Some(String::from(link)))
+                let link: String = redirect_link.unwrap_or_else(|| String::from(link));
+                let link: QuestionLink = QuestionLink::parse(&link)?;
+                Some(
+                    SearchHit::new(&link, index + 1)
+                        .with_title(Some(node.text()))
+                        .with_snippet(result_snippet(&node)),
+                )
             })
             .collect();
 
-        debug!("Links extrace from duckduckgo: {:?}", links);
-        if links.is_empty() {
+        debug!("Links extrace from duckduckgo: {:?}", hits);
+        if hits.is_empty() {
             warn!(
                 "Can't get search result from duckduckgo, source page\n{}",
                 page
             );
             return None;
         }
-        Some(links)
+        Some(hits)
+    }
+}
+
+/// Get snippet of the search result which contains the given result link.
+fn result_snippet(result_link: &Node) -> Option<String> {
+    let mut node: Option<Node> = result_link.parent();
+    while let Some(current) = node {
+        if current.is(Class("result__body")) {
+            return current
+                .find(Class("result__snippet"))
+                .next()
+                .map(|snippet| snippet.text());
+        }
+        node = current.parent();
     }
+    None
 }
 
 #[cfg(test)]
@@ -66,31 +94,59 @@ mod tests {
 <html>
     <body>
         <div class="result__body">
-            <a class="result__a" href="https://test_link1"></a>
+            <h2 class="result__title">
+                <a class="result__a" href="https://stackoverflow.com/questions/1/test-link">Test <b>link</b></a>
+            </h2>
+            <a class="result__snippet">the first  link</a>
         </div>
         div class="result__body">
-            <a class="result__a" href="https://test_link2"></a>
+            <a class="result__a" href="http://stackoverflow.com/questions/2/test-link?lq=1"></a>
         </div>
     </body>
 </html>"#,
         );
-        let possible_links: Option<Vec<String>> = engine.extract_links(&page);
-        assert_eq!(possible_links.is_some(), true);
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(&page);
+        assert!(possible_links.is_some());
         assert_eq!(
-            possible_links.unwrap(),
+            possible_links
+                .unwrap()
+                .into_iter()
+                .map(|hit| hit.link)
+                .collect::<Vec<String>>(),
             vec![
-                String::from("https://test_link1"),
-                String::from("https://test_link2")
+                String::from("https://stackoverflow.com/questions/1/test-link"),
+                String::from("https://stackoverflow.com/questions/2/test-link")
             ]
         )
     }
 
+    #[test]
+    fn test_extract_links_with_title_and_snippet() {
+        let engine = DuckDuckGo;
+        let page: &str = r#"
+<div class="result__body">
+    <h2 class="result__title">
+        <a class="result__a" href="https://stackoverflow.com/questions/1/c-sharp">Async in <b>C#</b></a>
+    </h2>
+    <a class="result__snippet">Use  <b>await</b> ...</a>
+</div>"#;
+        assert_eq!(
+            engine.extract_links(page).unwrap(),
+            vec![SearchHit {
+                link: String::from("https://stackoverflow.com/questions/1/c-sharp"),
+                title: Some(String::from("Async in C#")),
+                snippet: Some(String::from("Use await ...")),
+                rank: 1,
+            }]
+        );
+    }
+
     #[test]
     fn test_extract_links_when_there_are_no_links_available() {
         let engine = DuckDuckGo;
         let page: String = String::from("<html></html>");
-        let possible_links: Option<Vec<String>> = engine.extract_links(&page);
-        assert_eq!(possible_links.is_none(), true);
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(&page);
+        assert!(possible_links.is_none());
     }
 
     #[test]
@@ -100,22 +156,26 @@ mod tests {
 <html>
     <body>
         <div class="result__body">
-            <a class="result__a" href="/l/?kh=-1&uddg=https%3A%2F%2Ftest_link1"></a>
+            <a class="result__a" href="/l/?kh=-1&uddg=https%3A%2F%2Fstackoverflow.com%2Fquestions%2F1%2Ftest-link"></a>
         </div>
         div class="result__body">
-            <a class="result__a" href="/l/?kh=-1&uddg=https%3A%2F%2Ftest_link2"></a>
+            <a class="result__a" href="/l/?kh=-1&uddg=https%3A%2F%2Fstackoverflow.com%2Fa%2F2"></a>
         </div>
     </body>
 </html>"#,
         );
         let engine = DuckDuckGo;
-        let possible_links: Option<Vec<String>> = engine.extract_links(&page);
-        assert_eq!(possible_links.is_some(), true);
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(&page);
+        assert!(possible_links.is_some());
         assert_eq!(
-            possible_links.unwrap(),
+            possible_links
+                .unwrap()
+                .into_iter()
+                .map(|hit| hit.link)
+                .collect::<Vec<String>>(),
             vec![
-                String::from("https://test_link1"),
-                String::from("https://test_link2")
+                String::from("https://stackoverflow.com/questions/1/test-link"),
+                String::from("https://stackoverflow.com/a/2")
             ]
         )
     }
@@ -136,8 +196,8 @@ mod tests {
         </html>"#,
         );
         let engine = DuckDuckGo;
-        let possible_links: Option<Vec<String>> = engine.extract_links(&page);
-        assert_eq!(possible_links.is_some(), true);
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(&page);
+        assert!(possible_links.is_none());
     }
 
     #[test]
@@ -145,7 +205,7 @@ mod tests {
         let engine = DuckDuckGo;
         let result: String = engine.get_query_url(&String::from("how to write unit test"), true);
         assert_eq!(
-            "https://duckduckgo.com/html?q=site:stackoverflow.com%20how to write unit test&t=hj&ia=web",
+            "https://duckduckgo.com/html?q=site%3Astackoverflow.com+how+to+write+unit+test&t=hj&ia=web",
             result
         );
     }
@@ -155,7 +215,17 @@ mod tests {
         let engine = DuckDuckGo;
         let result: String = engine.get_query_url(&String::from("how to write unit test"), false);
         assert_eq!(
-            "http://duckduckgo.com/html?q=site:stackoverflow.com%20how to write unit test&t=hj&ia=web",
+            "http://duckduckgo.com/html?q=site%3Astackoverflow.com+how+to+write+unit+test&t=hj&ia=web",
+            result
+        );
+    }
+
+    #[test]
+    fn test_get_page_url() {
+        let engine = DuckDuckGo;
+        let result: String = engine.get_page_url("parse json", 1, true);
+        assert_eq!(
+            "https://duckduckgo.com/html?q=site%3Astackoverflow.com+parse+json&t=hj&ia=web&s=30",
             result
         );
     }
@@ -1,49 +1,79 @@
-use super::Engine;
+use super::{query_string, Engine, SearchHit};
+use crate::link::QuestionLink;
 use crate::search_config::SEARCH_CONFIG;
 use regex::Regex;
-use std::collections::HashSet;
+use select::document::Document;
+use select::predicate::Name;
+use std::collections::{HashMap, HashSet};
+
+/// Google shows 10 results in a page.
+const RESULTS_PER_PAGE: usize = 10;
 
 pub struct Google;
 
 impl Engine for Google {
-    fn get_query_url(&self, query: &str, use_https: bool) -> String {
-        if use_https {
-            format!(
-                "https://{}/search?q=site:stackoverflow.com%20{}&hl=en",
-                SEARCH_CONFIG.get_google_domain(),
-                query
-            )
-        } else {
-            format!(
-                "http://{}/search?q=site:stackoverflow.com%20{}&hl=en",
-                SEARCH_CONFIG.get_google_domain(),
-                query
-            )
+    fn get_page_url(&self, query: &str, page: usize, use_https: bool) -> String {
+        let scheme = if use_https { "https" } else { "http" };
+        let query: String = format!("site:stackoverflow.com {}", query);
+        // `start` is the offset of the first result in page.
+        let start: String = (page * RESULTS_PER_PAGE).to_string();
+        let mut params: Vec<(&str, &str)> = vec![("q", query.as_str()), ("hl", "en")];
+        if page > 0 {
+            params.push(("start", start.as_str()));
         }
+        format!(
+            "{}://{}/search?{}",
+            scheme,
+            SEARCH_CONFIG.get_google_domain(),
+            query_string(params)
+        )
     }
 
-    fn extract_links(&self, page: &str) -> Option<Vec<String>> {
-        let link_pattern =
-            Regex::new(r#"https?://*stackoverflow.com/questions/[0-9]*/[a-z0-9-]*"#).unwrap();
+    fn extract_links(&self, page: &str) -> Option<Vec<SearchHit>> {
+        // slug of link may be truncated, it doesn't matter because question is identified
+        // by id.
+        let link_pattern = Regex::new(
+            r#"https?://(?:[a-z]+\.)?stackoverflow\.com/(?:questions|q|a)/[0-9]+[^\s"'<>&]*"#,
+        )
+        .unwrap();
 
-        let mut link_set = HashSet::with_capacity(10);
-        for link in link_pattern.captures_iter(page) {
-            // the `link.get(0)` always return a entire matched string.
-            let link_str = link.get(0).unwrap().as_str();
-            if !link_str.contains("/url?") {
-                link_set.insert(link_str.to_string());
-            }
-        }
+        let titles: HashMap<String, String> = result_titles(page);
+        let mut keys: HashSet<String> = HashSet::new();
+        let hits: Vec<SearchHit> = link_pattern
+            .find_iter(page)
+            .filter_map(|link| QuestionLink::parse(link.as_str()))
+            .filter(|link| keys.insert(link.cache_key()))
+            .enumerate()
+            .map(|(index, link)| {
+                SearchHit::new(&link, index + 1).with_title(titles.get(&link.cache_key()).cloned())
+            })
+            .collect();
 
-        debug!("Links extract from google: {:?}", link_set);
-        if link_set.is_empty() {
+        debug!("Links extract from google: {:?}", hits);
+        if hits.is_empty() {
             None
         } else {
-            Some(link_set.into_iter().collect())
+            Some(hits)
         }
     }
 }
 
+/// Get titles of result links, google puts the title in `<h3>` under result link.
+///
+/// # Returns
+///
+/// Titles which are keyed by cache key of question link.
+fn result_titles(page: &str) -> HashMap<String, String> {
+    let doc: Document = Document::from(page);
+    doc.find(Name("a"))
+        .filter_map(|node| {
+            let link: QuestionLink = QuestionLink::parse(node.attr("href")?)?;
+            let title: String = node.find(Name("h3")).next()?.text();
+            Some((link.cache_key(), title))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,6 +88,7 @@ mod tests {
         <div class="g">
             <div class="r">
                 <a href="https://stackoverflow.com/questions/12/asdf">
+                    <h3>How to <em>asdf</em>?</h3>
                 </a>
             </div>
         </div>
@@ -70,12 +101,14 @@ mod tests {
     </body>
 </html>"#,
         );
-        let possible_links: Option<Vec<String>> = engine.extract_links(&page);
-        assert_eq!(possible_links.is_some(), true);
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(&page);
+        assert!(possible_links.is_some());
+        let hits: Vec<SearchHit> = possible_links.unwrap();
+        assert_eq!(hits[0].title, Some(String::from("How to asdf?")));
+        assert_eq!(hits[1].title, None);
         assert_eq!(
-            possible_links
-                .unwrap()
-                .into_iter()
+            hits.into_iter()
+                .map(|hit| hit.link)
                 .collect::<HashSet<String>>(),
             vec![
                 String::from("https://stackoverflow.com/questions/34/dfs"),
@@ -90,8 +123,8 @@ mod tests {
     fn test_extract_links_when_there_are_no_links_available() {
         let engine = Google;
         let page: String = String::from("<html></html>");
-        let possible_links: Option<Vec<String>> = engine.extract_links(&page);
-        assert_eq!(possible_links.is_none(), true);
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(&page);
+        assert!(possible_links.is_none());
     }
 
     #[test]
@@ -99,7 +132,7 @@ mod tests {
         let engine = Google;
         let result: String = engine.get_query_url(&String::from("how to write unit test"), true);
         assert_eq!(
-            "https://www.google.com/search?q=site:stackoverflow.com%20how to write unit test&hl=en",
+            "https://www.google.com/search?q=site%3Astackoverflow.com+how+to+write+unit+test&hl=en",
             result
         );
     }
@@ -109,7 +142,17 @@ mod tests {
         let engine = Google;
         let result: String = engine.get_query_url(&String::from("how to write unit test"), false);
         assert_eq!(
-            "http://www.google.com/search?q=site:stackoverflow.com%20how to write unit test&hl=en",
+            "http://www.google.com/search?q=site%3Astackoverflow.com+how+to+write+unit+test&hl=en",
+            result
+        );
+    }
+
+    #[test]
+    fn test_get_page_url() {
+        let engine = Google;
+        let result: String = engine.get_page_url("parse json", 1, true);
+        assert_eq!(
+            "https://www.google.com/search?q=site%3Astackoverflow.com+parse+json&hl=en&start=10",
             result
         );
     }
@@ -140,12 +183,13 @@ mod tests {
     </body>
 </html>"#,
         );
-        let possible_links: Option<Vec<String>> = engine.extract_links(&page);
-        assert_eq!(possible_links.is_some(), true);
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(&page);
+        assert!(possible_links.is_some());
         assert_eq!(
             possible_links
                 .unwrap()
                 .into_iter()
+                .map(|hit| hit.link)
                 .collect::<HashSet<String>>(),
             vec![
                 String::from("https://stackoverflow.com/questions/12/asdf"),
@@ -155,4 +199,26 @@ mod tests {
             .collect::<HashSet<String>>()
         )
     }
+
+    #[test]
+    fn test_extract_answer_links() {
+        let engine = Google;
+        let page: &str = r#"
+<a href="https://stackoverflow.com/a/5678/90"></a>
+<a href="https://stackoverflow.com/questions/12/asdf/3456#3456"></a>"#;
+        assert_eq!(
+            engine
+                .extract_links(page)
+                .unwrap()
+                .into_iter()
+                .map(|hit| hit.link)
+                .collect::<HashSet<String>>(),
+            vec![
+                String::from("https://stackoverflow.com/a/5678"),
+                String::from("https://stackoverflow.com/questions/12/asdf#3456"),
+            ]
+            .into_iter()
+            .collect::<HashSet<String>>()
+        )
+    }
 }
@@ -3,10 +3,17 @@ mod duckduckgo;
 mod google;
 mod stackoverflow;
 
-use crate::config::SearchEngine;
+use crate::config::{SearchEngine, SearchFilter};
 use crate::error::{Error, Result};
+use crate::link::QuestionLink;
 use crate::utils::random_agent;
 use reqwest::{Client, ClientBuilder, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use url::form_urlencoded;
+
+/// At most so many pages of search results are fetched for a query.
+const MAX_SEARCH_PAGES: usize = 3;
 
 /// Search engine trait
 pub trait Engine {
@@ -20,7 +27,18 @@ pub trait Engine {
     /// # Returns
     ///
     /// Return the query url, which can be fired with HTTP GET request.
-    fn get_query_url(&self, query: &str, use_https: bool) -> String;
+    fn get_query_url(&self, query: &str, use_https: bool) -> String {
+        self.get_page_url(query, 0, use_https)
+    }
+
+    /// Get url of the given page of search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The user input query information.
+    /// * `page` - The page of search results, starts from 0.
+    /// * `use_https` - Return query url which is https scheme or http scheme.
+    fn get_page_url(&self, query: &str, page: usize, use_https: bool) -> String;
 
     /// Extract stackoverflow links from given page.
     ///
@@ -28,8 +46,45 @@ pub trait Engine {
     ///
     /// * `page` - the search result page, which is mainly fetched from `http GET` method.
     ///
-    /// Links to the relative question, or returns None if we can't find it.
-    fn extract_links(&self, pages: &str) -> Option<Vec<String>>;
+    /// Search hits of the relative question, or returns None if we can't find it.  Links
+    /// which don't point to question or answer are ignored.
+    fn extract_links(&self, pages: &str) -> Option<Vec<SearchHit>>;
+}
+
+/// A search result which is returned by search engine.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// canonical link to question or answer.
+    pub link: String,
+    /// title of search result, it's usually the question title.
+    pub title: Option<String>,
+    /// text snippet which is displayed under the title.
+    pub snippet: Option<String>,
+    /// position (starts from 1) in search results page.
+    pub rank: usize,
+}
+
+impl SearchHit {
+    pub fn new(link: &QuestionLink, rank: usize) -> SearchHit {
+        SearchHit {
+            link: link.to_string(),
+            title: None,
+            snippet: None,
+            rank,
+        }
+    }
+
+    /// Set title of the hit, whitespaces are collapsed and empty title is ignored.
+    pub fn with_title(mut self, title: Option<String>) -> SearchHit {
+        self.title = title.and_then(|title| collapse_whitespace(&title));
+        self
+    }
+
+    /// Set snippet of the hit, whitespaces are collapsed and empty snippet is ignored.
+    pub fn with_snippet(mut self, snippet: Option<String>) -> SearchHit {
+        self.snippet = snippet.and_then(|snippet| collapse_whitespace(&snippet));
+        self
+    }
 }
 
 /// Search result links under the given search engine.
@@ -100,32 +155,171 @@ pub async fn search_links_with_client(
     search_engine: SearchEngine,
     client: &Client,
 ) -> Result<Vec<String>> {
-    let https_opts: Vec<bool> = vec![true, false];
+    search_links_with_filter(query, search_engine, client, &SearchFilter::default()).await
+}
+
+/// Search result links under the given search engine, with the given filter.
+///
+/// Only `StackOverflow` engine supports filtering search results, for other engines the
+/// filter is applied when answers are fetched, so all result links are returned.
+///
+/// Only the first page of search results is fetched, use `search_hits` to get more results.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use hors::{self, SearchEngine, SearchFilter};
+/// use reqwest::{Client, ClientBuilder};
+///
+/// # async fn run() {
+/// let client: Client = ClientBuilder::new().cookie_store(true).build().unwrap();
+/// let filter = SearchFilter {
+///     tags: vec![String::from("rust")],
+///     accepted_only: true,
+///     ..SearchFilter::default()
+/// };
+/// let target_links: Vec<String> = hors::search_links_with_filter(
+///     "how to parse json",
+///     SearchEngine::StackOverflow,
+///     &client,
+///     &filter,
+/// )
+/// .await
+/// .unwrap();
+/// assert_ne!(target_links.len(), 0);
+/// # }
+/// ```
+pub async fn search_links_with_filter(
+    query: &str,
+    search_engine: SearchEngine,
+    client: &Client,
+    filter: &SearchFilter,
+) -> Result<Vec<String>> {
+    let hits: Vec<SearchHit> = search_hits(query, search_engine, client, filter, 1).await?;
+    Ok(hits.into_iter().map(|hit| hit.link).collect())
+}
+
+/// Search result hits under the given search engine, with the given filter.
+///
+/// Different from links, hits contain title and snippet of search results, which are
+/// given by search engine.
+///
+/// If the first page has less than `numbers` hits, the following pages are fetched until
+/// `numbers` hits are collected, or there are no more results.  Hits of the same question
+/// are returned only once.
+///
+/// # Examples
+///
+/// ```rust
+/// use hors::{self, SearchEngine, SearchFilter, SearchHit};
+/// use reqwest::{Client, ClientBuilder};
+///
+/// # async fn run() {
+/// let client: Client = ClientBuilder::new().cookie_store(true).build().unwrap();
+/// let hits: Vec<SearchHit> = hors::search_hits(
+///     "how to parse json in rust",
+///     SearchEngine::Bing,
+///     &client,
+///     &SearchFilter::default(),
+///     10,
+/// )
+/// .await
+/// .unwrap();
+/// assert_eq!(hits[0].rank, 1);
+/// # }
+/// ```
+pub async fn search_hits(
+    query: &str,
+    search_engine: SearchEngine,
+    client: &Client,
+    filter: &SearchFilter,
+    numbers: usize,
+) -> Result<Vec<SearchHit>> {
     let engine: Box<dyn Engine> = match search_engine {
         SearchEngine::Bing => Box::new(bing::Bing),
         SearchEngine::Google => Box::new(google::Google),
         SearchEngine::DuckDuckGo => Box::new(duckduckgo::DuckDuckGo),
-        SearchEngine::StackOverflow => Box::new(stackoverflow::StackOverflow::default()),
+        SearchEngine::StackOverflow => {
+            Box::new(stackoverflow::StackOverflow::default().with_filter(filter.clone()))
+        }
     };
 
+    let mut hits: Vec<SearchHit> = search_page(query, &*engine, 0, client)
+        .await
+        .ok_or_else(|| Error::from_parse("Can't find search result..."))?;
+    for page in 1..MAX_SEARCH_PAGES {
+        if hits.len() >= numbers {
+            break;
+        }
+        let more_hits: Vec<SearchHit> = match search_page(query, &*engine, page, client).await {
+            Some(more_hits) => more_hits,
+            None => break,
+        };
+        debug!("Got {} more hits from page {}", more_hits.len(), page);
+        if !merge_hits(&mut hits, more_hits) {
+            break;
+        }
+    }
+    Ok(hits)
+}
+
+/// Search the given page of results, https is tried first, then http.
+async fn search_page(
+    query: &str,
+    search_engine: &dyn Engine,
+    page: usize,
+    client: &Client,
+) -> Option<Vec<SearchHit>> {
+    let https_opts: Vec<bool> = vec![true, false];
     for opt in https_opts {
-        let fetch_url: String = get_query_url(query, &*engine, opt);
-        let page: Result<String> = fetch(&fetch_url, client).await;
-        match page {
+        let fetch_url: String = search_engine.get_page_url(query, page, opt);
+        match fetch(&fetch_url, client).await {
             Ok(page) => {
-                let extract_results = extract_links(&page, &*engine);
-                if let Some(links) = extract_results {
-                    return Ok(links);
+                let extract_results = extract_links(&page, search_engine);
+                if extract_results.is_some() {
+                    return extract_results;
                 }
             }
             Err(e) => warn!("Erorr for get url {}: {}", fetch_url, e),
         }
     }
-    Err(Error::from_parse("Can't find search result..."))
+    None
 }
 
-fn get_query_url(query: &str, search_engine: &dyn Engine, use_https: bool) -> String {
-    search_engine.get_query_url(query, use_https)
+/// Append hits of the next page, hits which are already found are ignored, and rank
+/// continues from the last hit.
+///
+/// # Returns
+///
+/// If there are new hits.
+fn merge_hits(hits: &mut Vec<SearchHit>, more_hits: Vec<SearchHit>) -> bool {
+    let mut keys: HashSet<String> = hits
+        .iter()
+        .map(|hit| crate::link::cache_key(&hit.link))
+        .collect();
+    let last_rank: usize = hits.iter().map(|hit| hit.rank).max().unwrap_or_default();
+    let count: usize = hits.len();
+    hits.extend(
+        more_hits
+            .into_iter()
+            .filter(|hit| keys.insert(crate::link::cache_key(&hit.link)))
+            .map(|hit| SearchHit {
+                rank: last_rank + hit.rank,
+                ..hit
+            }),
+    );
+    hits.len() > count
+}
+
+/// Make query string of url from the given parameters.
+///
+/// Names and values are percent-encoded, so queries like `c# async` and `c++ templates`
+/// are not truncated or mangled.
+fn query_string<'a>(params: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(params)
+        .finish()
 }
 
 /// Fetch actual page according to given url.
@@ -134,7 +328,7 @@ fn get_query_url(query: &str, search_engine: &dyn Engine, use_https: bool) -> St
 ///
 /// * `search_url` - The url which should lead to search result page.
 /// * `client` - An instance of `request::Client` object which can use to fire http request,
-///              please ensure that it's build with cookie_store(true) option.
+///   please ensure that it's build with cookie_store(true) option.
 ///
 /// # Returns
 ///
@@ -159,7 +353,88 @@ async fn fetch(search_url: &str, client: &Client) -> Result<String> {
 ///
 /// # Returns
 ///
-/// Links to the relative question, or returns None if we can't find it.
-fn extract_links(page: &str, search_engine: &dyn Engine) -> Option<Vec<String>> {
-    search_engine.extract_links(page)
+/// Search hits of the relative question, or returns None if we can't find it.  Hits
+/// of the same question are returned only once.
+fn extract_links(page: &str, search_engine: &dyn Engine) -> Option<Vec<SearchHit>> {
+    let mut keys: HashSet<String> = HashSet::new();
+    let hits: Vec<SearchHit> = search_engine
+        .extract_links(page)?
+        .into_iter()
+        .filter(|hit| keys.insert(crate::link::cache_key(&hit.link)))
+        .collect();
+    Some(hits)
+}
+
+fn collapse_whitespace(text: &str) -> Option<String> {
+    Some(text.split_whitespace().collect::<Vec<&str>>().join(" ")).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::Url;
+
+    #[test]
+    fn test_query_url_keeps_tricky_query() {
+        let engines: Vec<Box<dyn Engine>> = vec![
+            Box::new(bing::Bing),
+            Box::new(google::Google),
+            Box::new(duckduckgo::DuckDuckGo),
+            Box::new(stackoverflow::StackOverflow::default()),
+        ];
+        for query in [
+            "c# async",
+            "c++ templates",
+            "a & b?",
+            "%20 #1",
+            "日本語 文字列",
+        ]
+        .iter()
+        {
+            for engine in engines.iter() {
+                let url: Url = Url::parse(&engine.get_query_url(query, true)).unwrap();
+                assert_eq!(url.fragment(), None);
+                let q: String = url
+                    .query_pairs()
+                    .find(|(name, _)| name == "q")
+                    .map(|(_, value)| value.into_owned())
+                    .unwrap();
+                assert!(q.ends_with(query), "{} is mangled to {}", query, q);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extract_links_dedup_by_question() {
+        let page: &str = r#"
+<li class="b_algo"><h2><a href="https://stackoverflow.com/questions/12/parse-json"></a></h2></li>
+<li class="b_algo"><h2><a href="http://m.stackoverflow.com/questions/12/parse-js?lq=1"></a></h2></li>
+<li class="b_algo"><h2><a href="https://stackoverflow.com/questions/34/read-file"></a></h2></li>"#;
+        let hits: Vec<SearchHit> = extract_links(page, &bing::Bing).unwrap();
+        assert_eq!(
+            hits.iter()
+                .map(|hit| (hit.link.as_str(), hit.rank))
+                .collect::<Vec<(&str, usize)>>(),
+            vec![
+                ("https://stackoverflow.com/questions/12/parse-json", 1),
+                ("https://stackoverflow.com/questions/34/read-file", 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_hits() {
+        let hit = |id: usize, rank: usize| SearchHit {
+            link: format!("https://stackoverflow.com/questions/{}", id),
+            title: None,
+            snippet: None,
+            rank,
+        };
+        let mut hits: Vec<SearchHit> = vec![hit(1, 1), hit(2, 3)];
+        assert!(merge_hits(&mut hits, vec![hit(2, 1), hit(3, 2)]));
+        assert_eq!(hits, vec![hit(1, 1), hit(2, 3), hit(3, 5)]);
+        // page which has no new hits.
+        assert!(!merge_hits(&mut hits, vec![hit(1, 1)]));
+        assert_eq!(hits.len(), 3);
+    }
 }
@@ -1,9 +1,13 @@
-use super::Engine;
+use super::{query_string, Engine, SearchHit};
+use crate::config::SearchFilter;
+use crate::link::QuestionLink;
+use select::document::Document;
 use serde::Deserialize;
 
-// filter str can help us make less network traffic
-// We just need the question_link, and more quota information.
-const FILTER_STR: &str = "!)8aEDWeNHfyXN.d";
+// filter str decides which fields are returned by api.
+// We need the question link, question title, and quota information, all of them are
+// included in the built-in default filter.
+const FILTER_STR: &str = "default";
 
 const VERSION: &str = "2.2";
 const API_DOMAIN: &str = "api.stackexchange.com";
@@ -17,11 +21,45 @@ pub struct StackOverflow {
     api_key: String,
     /// The length of questions we need to fetch in a query.
     page_size: u8,
+    /// Filters which are mapped onto advanced search parameters.
+    filter: SearchFilter,
 }
 
 impl StackOverflow {
     pub fn new(api_key: String, page_size: u8) -> StackOverflow {
-        StackOverflow { api_key, page_size }
+        StackOverflow {
+            api_key,
+            page_size,
+            filter: SearchFilter::default(),
+        }
+    }
+
+    /// Search only questions which match the filter.
+    ///
+    /// Note that vote score of answer can't be filtered by advanced search, it's checked
+    /// after answers are fetched.
+    pub fn with_filter(mut self, filter: SearchFilter) -> StackOverflow {
+        self.filter = filter;
+        self
+    }
+
+    /// Make advanced search parameters from filter.
+    fn filter_params(&self) -> Vec<(&'static str, String)> {
+        let mut params: Vec<(&'static str, String)> = Vec::new();
+        if !self.filter.tags.is_empty() {
+            params.push(("tagged", self.filter.tags.join(";")));
+        }
+        if self.filter.accepted_only {
+            params.push(("accepted", String::from("True")));
+        }
+        if let Some(since) = self.filter.since {
+            params.push(("fromdate", since.timestamp().to_string()));
+        }
+        if let Some(until) = self.filter.until {
+            // the whole day of `until` is included.
+            params.push(("todate", (until.timestamp() + 86399).to_string()));
+        }
+        params
     }
 }
 
@@ -49,25 +87,66 @@ impl Questions {
 #[derive(Deserialize, Debug)]
 struct QuestionItem {
     link: String,
+    /// question title, html entities like `&quot;` are escaped.
+    #[serde(default)]
+    title: Option<String>,
 }
 
 impl Engine for StackOverflow {
-    fn get_query_url(&self, query: &str, use_https: bool) -> String {
+    fn get_page_url(&self, query: &str, page: usize, use_https: bool) -> String {
         let scheme = if use_https { "https" } else { "http" };
+        let page_size: String = self.page_size.to_string();
+        let mut extra_params: Vec<(&str, String)> = self.filter_params();
+        // pages of api start from 1.
+        if page > 0 {
+            extra_params.push(("page", (page + 1).to_string()));
+        }
+        let params = vec![
+            ("key", self.api_key.as_str()),
+            ("pagesize", page_size.as_str()),
+            ("site", "stackoverflow"),
+            ("order", "desc"),
+            ("sort", "relevance"),
+            ("q", query),
+            ("filter", FILTER_STR),
+        ]
+        .into_iter()
+        .chain(
+            extra_params
+                .iter()
+                .map(|(name, value)| (*name, value.as_str())),
+        );
         format!(
-                "{}://{}/{}/search/advanced?key={}&pagesize={}&site=stackoverflow&order=desc&sort=relevance&q={}&filter={}",
-                scheme, API_DOMAIN, VERSION, self.api_key, self.page_size, query, FILTER_STR
+            "{}://{}/{}/search/advanced?{}",
+            scheme,
+            API_DOMAIN,
+            VERSION,
+            query_string(params)
         )
     }
 
-    fn extract_links(&self, pages: &str) -> Option<Vec<String>> {
+    fn extract_links(&self, pages: &str) -> Option<Vec<SearchHit>> {
         let deser_result = serde_json::from_str::<Questions>(pages);
         match deser_result {
             Err(e) => {
                 warn!("Deserialize json response failed: {}", e);
                 None
             }
-            Ok(questions) => Some(questions.into_iter().map(|q| q.link).collect()),
+            Ok(questions) => Some(
+                questions
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(index, q)| {
+                        let link: QuestionLink = QuestionLink::parse(&q.link)?;
+                        let title: Option<String> = q.title.and_then(|title| {
+                            Document::from(title.as_str())
+                                .nth(0)
+                                .map(|node| node.text())
+                        });
+                        Some(SearchHit::new(&link, index + 1).with_title(title))
+                    })
+                    .collect(),
+            ),
         }
     }
 }
@@ -83,7 +162,7 @@ mod tests {
         assert_eq!(
             result,
             format!("https://api.stackexchange.com/2.2/search/advanced?\
-            key=aaa&pagesize=10&site=stackoverflow&order=desc&sort=relevance&q=how to write unit test&filter={}", "!)8aEDWeNHfyXN.d")
+            key=aaa&pagesize=10&site=stackoverflow&order=desc&sort=relevance&q=how+to+write+unit+test&filter={}", "default")
         );
     }
 
@@ -94,48 +173,77 @@ mod tests {
         assert_eq!(
             result,
             format!("http://api.stackexchange.com/2.2/search/advanced?\
-            key=aaa&pagesize=10&site=stackoverflow&order=desc&sort=relevance&q=how to write unit test&filter={}", "!)8aEDWeNHfyXN.d")
+            key=aaa&pagesize=10&site=stackoverflow&order=desc&sort=relevance&q=how+to+write+unit+test&filter={}", "default")
         )
     }
 
+    #[test]
+    fn test_get_query_url_with_filter() {
+        let filter = SearchFilter {
+            tags: vec![String::from("rust"), String::from("serde")],
+            min_score: Some(10),
+            since: "2020-01-01".parse().ok(),
+            until: "2020-01-01".parse().ok(),
+            accepted_only: true,
+        };
+        let engine = StackOverflow::new("aaa".to_string(), 10).with_filter(filter);
+        let result: String = engine.get_query_url("parse json", true);
+        assert!(result
+            .ends_with("&tagged=rust%3Bserde&accepted=True&fromdate=1577836800&todate=1577923199"));
+    }
+
+    #[test]
+    fn test_get_page_url() {
+        let engine = StackOverflow::new("aaa".to_string(), 10);
+        assert!(!engine.get_page_url("parse json", 0, true).contains("page="));
+        assert!(engine
+            .get_page_url("parse json", 1, true)
+            .ends_with("&page=2"));
+    }
+
     #[test]
     fn test_extract_links() {
         let engine = StackOverflow::new("aaa".to_string(), 10);
         let result = engine.extract_links(
             r#"
             {
-                "items": [{"link": "http://aaa.com/"}, {"link": "http://aaa.com/bb"}],
+                "items": [
+                    {"link": "https://stackoverflow.com/questions/12/aa", "title": "What&#39;s &quot;C#&quot;?"},
+                    {"link": "http://aaa.com/bb"}
+                ],
                 "quota_max": 10,
                 "quota_remaining": 9
         }"#,
         );
-        assert_eq!(result.is_some(), true);
+        assert!(result.is_some());
         assert_eq!(
             result.unwrap(),
-            vec![
-                String::from("http://aaa.com/"),
-                String::from("http://aaa.com/bb")
-            ]
+            vec![SearchHit {
+                link: String::from("https://stackoverflow.com/questions/12/aa"),
+                title: Some(String::from("What's \"C#\"?")),
+                snippet: None,
+                rank: 1,
+            }]
         )
     }
 
     #[test]
     fn test_extract_links_with_wrong_format() {
         let engine = StackOverflow::new("aaa".to_string(), 10);
-        let possible_links: Option<Vec<String>> = engine.extract_links(
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(
             r#"
             {
                 "items": [],
                 "quota_remaining": 9
             }"#,
         );
-        assert_eq!(possible_links.is_none(), true);
+        assert!(possible_links.is_none());
     }
 
     #[test]
     fn test_extract_links_with_no_items() {
         let engine = StackOverflow::new("aaa".to_string(), 10);
-        let possible_links: Option<Vec<String>> = engine.extract_links(
+        let possible_links: Option<Vec<SearchHit>> = engine.extract_links(
             r#"
             {
                 "items": [],
@@ -143,8 +251,8 @@ mod tests {
                 "quota_remaining": 9
             }"#,
         );
-        let expected: Vec<String> = vec![];
-        assert_eq!(possible_links.is_some(), true);
+        let expected: Vec<SearchHit> = vec![];
+        assert!(possible_links.is_some());
         assert_eq!(possible_links.unwrap(), expected);
     }
 }
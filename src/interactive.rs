@@ -0,0 +1,551 @@
+//! Interactive mode, which lets user pick search results and browse answers in terminal.
+//!
+//! Search results are displayed in a list, and answers of the highlighted question are
+//! displayed below the list.  Pages are fetched when a question is selected, rather
+//! than all up-front.
+
+use crate::answer::{copy_to_clipboard, extract_question, get_question_answers, Answer};
+use crate::answer::{PageCache, Stash, StashedAnswer};
+use crate::config::Config;
+use crate::engine::SearchHit;
+use crate::error::Result;
+use crate::link::QuestionLink;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{
+    self, Clear, ClearType, DisableLineWrap, EnableLineWrap, EnterAlternateScreen,
+    LeaveAlternateScreen,
+};
+use crossterm::{execute, queue};
+use reqwest::{Client, Url};
+use std::io::{self, Stdout, Write};
+use std::process::{Command, Stdio};
+
+/// Key bindings which are displayed in status line.
+const HELP: &str =
+    "↑↓ select · enter load · ←→ answers · pgup/pgdn scroll · c copy · s stash · o open · q quit";
+
+/// A search result in the list.
+#[derive(Debug)]
+struct Entry {
+    /// question link.
+    link: String,
+    /// question title, it's given by search engine, or extracted from link.
+    title: String,
+    /// snippet of search result.
+    snippet: Option<String>,
+    /// the site where question comes from, like `stackoverflow.com`.
+    site: String,
+    /// answers of the question, it's None until the question is loaded.
+    answers: Option<Vec<Answer>>,
+    /// index of the answer which is displayed.
+    answer_index: usize,
+}
+
+impl Entry {
+    fn new(link: &str, hit: Option<&SearchHit>) -> Entry {
+        let url: Option<Url> = Url::parse(link).ok();
+        Entry {
+            link: link.to_string(),
+            title: hit
+                .and_then(|hit| hit.title.clone())
+                .or_else(|| url.as_ref().map(|url| extract_question(url.path())))
+                .unwrap_or_else(|| link.to_string()),
+            snippet: hit.and_then(|hit| hit.snippet.clone()),
+            site: url
+                .as_ref()
+                .and_then(|url| url.host_str())
+                .unwrap_or_default()
+                .to_string(),
+            answers: None,
+            answer_index: 0,
+        }
+    }
+
+    fn current_answer(&self) -> Option<&Answer> {
+        self.answers
+            .as_ref()
+            .and_then(|answers| answers.get(self.answer_index))
+    }
+}
+
+/// State of the interactive picker.
+#[derive(Debug)]
+struct Picker {
+    /// the query which search results come from.
+    query: String,
+    entries: Vec<Entry>,
+    /// index of highlighted entry.
+    selected: usize,
+    /// scroll offset of answer preview.
+    scroll: usize,
+    /// message which is displayed in status line, key bindings are displayed if it's None.
+    message: Option<String>,
+}
+
+impl Picker {
+    /// Create picker from search result links, links which are not questions are ignored.
+    ///
+    /// Titles and snippets are taken from search hits of links if possible.
+    fn new(query: &str, links: &[String], hits: &[SearchHit]) -> Picker {
+        Picker {
+            query: query.to_string(),
+            entries: links
+                .iter()
+                .filter(|link| QuestionLink::parse(link).is_some())
+                .map(|link| Entry::new(link, hits.iter().find(|hit| &hit.link == link)))
+                .collect(),
+            selected: 0,
+            scroll: 0,
+            message: None,
+        }
+    }
+
+    fn current_entry(&self) -> Option<&Entry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Links of questions whose answers are loaded.
+    fn loaded_links(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                entry
+                    .answers
+                    .as_ref()
+                    .is_some_and(|answers| !answers.is_empty())
+            })
+            .map(|entry| entry.link.clone())
+            .collect()
+    }
+
+    fn select_next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+            self.scroll = 0;
+        }
+    }
+
+    fn select_previous(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+            self.scroll = 0;
+        }
+    }
+
+    fn next_answer(&mut self) {
+        if let Some(entry) = self.entries.get_mut(self.selected) {
+            let count: usize = entry.answers.as_ref().map_or(0, Vec::len);
+            if entry.answer_index + 1 < count {
+                entry.answer_index += 1;
+                self.scroll = 0;
+            }
+        }
+    }
+
+    fn previous_answer(&mut self) {
+        if let Some(entry) = self.entries.get_mut(self.selected) {
+            if entry.answer_index > 0 {
+                entry.answer_index -= 1;
+                self.scroll = 0;
+            }
+        }
+    }
+
+    fn scroll_down(&mut self, lines: usize) {
+        let content_lines: usize = self
+            .current_entry()
+            .and_then(Entry::current_answer)
+            .map_or(0, |answer| answer.content.lines().count());
+        self.scroll = (self.scroll + lines).min(content_lines.saturating_sub(1));
+    }
+
+    fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    /// Height of result list, it takes at most one third of screen.
+    fn list_height(&self, height: usize) -> usize {
+        self.entries.len().min(height / 3).max(1)
+    }
+
+    /// Render picker into lines which fill the screen.
+    fn render(&self, width: usize, height: usize) -> Vec<String> {
+        let list_height: usize = self.list_height(height);
+        let first: usize = (self.selected + 1).saturating_sub(list_height);
+        let mut lines: Vec<String> = Vec::new();
+        for (index, entry) in self
+            .entries
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(list_height)
+        {
+            let marker: &str = if index == self.selected { ">" } else { " " };
+            let score: String = entry
+                .current_answer()
+                .and_then(|answer| answer.score)
+                .map(|score| format!(" ▲ {}", score))
+                .unwrap_or_default();
+            lines.push(format!(
+                "{} {}. {}{} ({})",
+                marker,
+                index + 1,
+                entry.title,
+                score,
+                entry.site
+            ));
+        }
+        if self.entries.is_empty() {
+            lines.push(String::from("  No question is found."));
+        }
+        lines.push("─".repeat(width));
+
+        let preview_height: usize = height.saturating_sub(lines.len() + 1);
+        let preview: Vec<String> = match self.current_entry() {
+            Some(entry) => match (&entry.answers, entry.current_answer()) {
+                (None, _) => {
+                    let mut preview: Vec<String> = entry.snippet.iter().cloned().collect();
+                    preview.push(String::from("Press enter to load answers."));
+                    preview
+                }
+                (Some(_), None) => vec![String::from("No answer is available.")],
+                (Some(answers), Some(answer)) => {
+                    let mut preview: Vec<String> = vec![format!(
+                        "Answer {}/{}",
+                        entry.answer_index + 1,
+                        answers.len()
+                    )];
+                    preview.extend(answer.content.lines().skip(self.scroll).map(String::from));
+                    preview
+                }
+            },
+            None => Vec::new(),
+        };
+        let preview_len: usize = preview.len();
+        lines.extend(preview.into_iter().take(preview_height));
+        for _ in preview_len..preview_height {
+            lines.push(String::new());
+        }
+        lines.push(self.message.clone().unwrap_or_else(|| HELP.to_string()));
+        lines
+    }
+}
+
+/// Run interactive mode with the given search result links of `query`.
+///
+/// It returns when user quits.
+///
+/// # Returns
+///
+/// Links of questions whose answers are loaded, they're in the order of given links.
+pub async fn run_interactive(
+    query: &str,
+    links: &[String],
+    conf: Config,
+    client: Client,
+    page_cache: PageCache,
+) -> Result<Vec<String>> {
+    let mut picker: Picker = Picker::new(query, links, conf.search_hits());
+    let mut stdout: Stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, Hide, DisableLineWrap)?;
+
+    let result: Result<()> =
+        event_loop(&mut picker, &conf, &client, &page_cache, &mut stdout).await;
+
+    execute!(stdout, EnableLineWrap, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result.map(|_| picker.loaded_links())
+}
+
+async fn event_loop(
+    picker: &mut Picker,
+    conf: &Config,
+    client: &Client,
+    page_cache: &PageCache,
+    stdout: &mut Stdout,
+) -> Result<()> {
+    // load the first question, so user can see an answer at once.
+    load_answers(picker, conf, client, page_cache, stdout).await?;
+    loop {
+        draw(picker, stdout)?;
+        let (_, height) = terminal::size()?;
+        let page: usize = (height as usize / 2).max(1);
+        let key: KeyEvent = match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => key,
+            _ => continue,
+        };
+        picker.message = None;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+            KeyCode::Up | KeyCode::Char('k') => picker.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => picker.select_next(),
+            KeyCode::Enter => load_answers(picker, conf, client, page_cache, stdout).await?,
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('n') => picker.next_answer(),
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('p') => picker.previous_answer(),
+            KeyCode::PageDown | KeyCode::Char(' ') => picker.scroll_down(page),
+            KeyCode::PageUp | KeyCode::Char('b') => picker.scroll_up(page),
+            KeyCode::Char('c') => copy_answer(picker),
+            KeyCode::Char('s') => stash_answer(picker),
+            KeyCode::Char('o') => open_answer(picker),
+            _ => continue,
+        }
+    }
+    Ok(())
+}
+
+fn draw(picker: &Picker, stdout: &mut Stdout) -> Result<()> {
+    let (width, height) = terminal::size()?;
+    queue!(stdout, Clear(ClearType::All))?;
+    for (row, line) in picker
+        .render(width as usize, height as usize)
+        .iter()
+        .enumerate()
+    {
+        // reset style at the end of line, so colors of answer don't leak into next line.
+        queue!(stdout, MoveTo(0, row as u16), Print(line), Print("\x1b[0m"))?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Load answers of the highlighted question if they're not loaded yet.
+async fn load_answers(
+    picker: &mut Picker,
+    conf: &Config,
+    client: &Client,
+    page_cache: &PageCache,
+    stdout: &mut Stdout,
+) -> Result<()> {
+    let link: String = match picker.current_entry() {
+        Some(entry) if entry.answers.is_none() => entry.link.clone(),
+        _ => return Ok(()),
+    };
+    picker.message = Some(format!("Loading {} ...", link));
+    draw(picker, stdout)?;
+    match get_question_answers(&link, conf, client, page_cache).await {
+        Ok(answers) => {
+            picker.entries[picker.selected].answers = Some(answers);
+            picker.message = None;
+        }
+        Err(err) => picker.message = Some(format!("Load answers failed: {}", err)),
+    }
+    Ok(())
+}
+
+fn copy_answer(picker: &mut Picker) {
+    let code: Option<String> = picker
+        .current_entry()
+        .and_then(Entry::current_answer)
+        .and_then(|answer| answer.code.clone());
+    picker.message = Some(match code {
+        Some(code) => match copy_to_clipboard(&code) {
+            Ok(_) => String::from("Code is copied to clipboard."),
+            Err(err) => format!("Copy code failed: {}", err),
+        },
+        None => String::from("No code to copy."),
+    });
+}
+
+fn stash_answer(picker: &mut Picker) {
+    let entry: &Entry = match picker.current_entry() {
+        Some(entry) => entry,
+        None => return,
+    };
+    let answer: &Answer = match entry.current_answer() {
+        Some(answer) => answer,
+        None => {
+            picker.message = Some(String::from("No answer to stash."));
+            return;
+        }
+    };
+    let name: String = stash_name(&entry.title);
+    let stashed = StashedAnswer::new(
+        picker.query.clone(),
+        vec![answer.permalink.clone()],
+        answer.content.clone(),
+    );
+    let result: Result<()> = Stash::load().and_then(|mut stash| {
+        stash.put(name.clone(), stashed);
+        stash.save()
+    });
+    picker.message = Some(match result {
+        Ok(_) => format!("Answer is stashed as '{}'.", name),
+        Err(err) => format!("Stash answer failed: {}", err),
+    });
+}
+
+/// Make stash name from question title, characters other than letters and digits are
+/// replaced with `-`, so the name can be typed in shell without quoting.
+fn stash_name(title: &str) -> String {
+    let name: String = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+    if name.is_empty() {
+        String::from("answer")
+    } else {
+        name
+    }
+}
+
+fn open_answer(picker: &mut Picker) {
+    let link: String = match picker.current_entry() {
+        Some(entry) => entry
+            .current_answer()
+            .map(|answer| answer.permalink.clone())
+            .unwrap_or_else(|| entry.link.clone()),
+        None => return,
+    };
+    picker.message = Some(match open_link(&link) {
+        Ok(_) => format!("Opened {}", link),
+        Err(err) => format!("Open {} failed: {}", link, err),
+    });
+}
+
+/// Open link with the default browser.
+fn open_link(link: &str) -> io::Result<()> {
+    let mut command: Command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(link)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(content: &str, score: i32) -> Answer {
+        Answer {
+            content: content.to_string(),
+            code: None,
+            permalink: String::from("https://stackoverflow.com/a/1"),
+            score: Some(score),
+            question_title: None,
+        }
+    }
+
+    fn picker() -> Picker {
+        Picker::new(
+            "parse json",
+            &[
+                String::from("https://stackoverflow.com/questions/1/parse-json"),
+                String::from("https://stackoverflow.com/tags/json"),
+                String::from("https://superuser.com/questions/2/undo-commit"),
+            ],
+            &[SearchHit {
+                link: String::from("https://superuser.com/questions/2/undo-commit"),
+                title: Some(String::from("Undo \"git commit\"?")),
+                snippet: Some(String::from("Use git reset ...")),
+                rank: 2,
+            }],
+        )
+    }
+
+    #[test]
+    fn test_picker_ignores_links_which_are_not_questions() {
+        let picker = picker();
+        assert_eq!(picker.entries.len(), 2);
+        assert_eq!(picker.entries[0].title, "parse json");
+        assert_eq!(picker.entries[1].title, "Undo \"git commit\"?");
+        assert_eq!(picker.entries[1].site, "superuser.com");
+    }
+
+    #[test]
+    fn test_picker_keeps_answer_links() {
+        let picker = Picker::new(
+            "",
+            &[String::from("https://stackoverflow.com/a/24680")],
+            &[],
+        );
+        assert_eq!(picker.entries.len(), 1);
+        assert_eq!(picker.entries[0].site, "stackoverflow.com");
+    }
+
+    #[test]
+    fn test_picker_loaded_links() {
+        let mut picker = picker();
+        assert!(picker.loaded_links().is_empty());
+        picker.entries[1].answers = Some(vec![answer("a", 1)]);
+        assert_eq!(
+            picker.loaded_links(),
+            vec![String::from(
+                "https://superuser.com/questions/2/undo-commit"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_stash_name() {
+        assert_eq!(stash_name("Undo \"git commit\"?"), "Undo-git-commit");
+        assert_eq!(stash_name("a/b c"), "a-b-c");
+        assert_eq!(stash_name("??"), "answer");
+    }
+
+    #[test]
+    fn test_picker_select() {
+        let mut picker = picker();
+        picker.select_previous();
+        assert_eq!(picker.selected, 0);
+        picker.select_next();
+        picker.select_next();
+        assert_eq!(picker.selected, 1);
+    }
+
+    #[test]
+    fn test_picker_step_through_answers() {
+        let mut picker = picker();
+        picker.next_answer();
+        assert_eq!(picker.entries[0].answer_index, 0);
+
+        picker.entries[0].answers = Some(vec![answer("a\nb\nc", 10), answer("d", 2)]);
+        picker.scroll_down(10);
+        assert_eq!(picker.scroll, 2);
+        picker.next_answer();
+        picker.next_answer();
+        assert_eq!(picker.entries[0].answer_index, 1);
+        assert_eq!(picker.scroll, 0);
+        picker.previous_answer();
+        assert_eq!(picker.entries[0].answer_index, 0);
+    }
+
+    #[test]
+    fn test_picker_render() {
+        let mut picker = picker();
+        picker.entries[0].answers = Some(vec![answer("a\nb", 10)]);
+        assert_eq!(
+            picker.render(40, 9),
+            vec![
+                String::from("> 1. parse json ▲ 10 (stackoverflow.com)"),
+                String::from("  2. Undo \"git commit\"? (superuser.com)"),
+                "─".repeat(40),
+                String::from("Answer 1/1"),
+                String::from("a"),
+                String::from("b"),
+                String::new(),
+                String::new(),
+                HELP.to_string(),
+            ]
+        );
+
+        picker.select_next();
+        assert_eq!(picker.render(40, 9)[3], "Use git reset ...");
+        assert_eq!(picker.render(40, 9)[4], "Press enter to load answers.");
+    }
+}
@@ -85,14 +85,28 @@ extern crate lazy_static;
 pub mod answer;
 pub mod config;
 pub mod engine;
+pub mod interactive;
+pub mod link;
+pub mod query;
 
 mod error;
 mod output;
 mod search_config;
 mod utils;
 
-pub use answer::{clear_local_cache, get_answers, get_answers_with_client, SPLITTER};
-pub use config::{Config, OutputOption, PagingOption, SearchEngine};
-pub use engine::{search_links, search_links_with_client};
+pub use answer::{
+    clear_local_cache, collect_answers, get_answers, get_answers_with_cache,
+    get_answers_with_client, get_question_answers, Answer, Answers, History, HistoryEntry,
+    HistoryRef, LastQuery, Outcome, PageCache, Stash, StashedAnswer, SPLITTER,
+};
+pub use config::{
+    CodeStyle, Config, Date, ExportFormat, HyperlinkOption, OutputOption, PagingOption,
+    SearchEngine, SearchFilter,
+};
+pub use engine::{
+    search_hits, search_links, search_links_with_client, search_links_with_filter, SearchHit,
+};
 pub use error::{Error, Result};
+pub use link::QuestionLink;
 pub use output::Output;
+pub use query::query_from_error;
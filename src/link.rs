@@ -0,0 +1,238 @@
+//! Canonical link to question of stackexchange sites.
+//!
+//! Search engines return links to the same question in many forms, like `http` or
+//! `https`, `m.stackoverflow.com`, with query string, or with different (even truncated)
+//! slugs.  They're parsed into `QuestionLink`, so the same question isn't fetched or
+//! cached twice.
+
+use reqwest::Url;
+use std::fmt;
+
+/// Sites of stackexchange network which are not subdomain of `stackexchange.com`.
+const SITES: [&str; 6] = [
+    "stackoverflow.com",
+    "superuser.com",
+    "serverfault.com",
+    "askubuntu.com",
+    "mathoverflow.net",
+    "stackapps.com",
+];
+
+/// Link to a question, or to a specific answer of question.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuestionLink {
+    /// site host, like `stackoverflow.com`.
+    site: String,
+    /// question id, it's unknown for answer link like `/a/67890`.
+    question_id: Option<u64>,
+    /// question title in link, like `how-to-parse-json`, it may be truncated.
+    slug: Option<String>,
+    /// id of the answer which link points to.
+    answer_id: Option<u64>,
+}
+
+impl QuestionLink {
+    /// Parse question link from any form of link.
+    ///
+    /// These forms are supported, along with `http` scheme, `www.` and `m.` subdomains,
+    /// query string and anchors:
+    /// * `/questions/12345`, `/questions/12345/slug` and `/q/12345` link to question.
+    /// * `/questions/12345/slug/67890`, `/questions/12345/slug#67890`, `/a/67890` and
+    ///   `/answers/67890` link to answer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hors::QuestionLink;
+    ///
+    /// let link = QuestionLink::parse("http://m.stackoverflow.com/questions/12345/parse-json?lq=1#67890").unwrap();
+    /// assert_eq!(link.to_string(), "https://stackoverflow.com/questions/12345/parse-json#67890");
+    /// assert_eq!(link.cache_key(), "https://stackoverflow.com/questions/12345");
+    /// assert_eq!(link.answer_id(), Some(67890));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// The link, or None if it doesn't link to question or answer of stackexchange sites.
+    pub fn parse(link: &str) -> Option<QuestionLink> {
+        let url: Url = Url::parse(link.trim()).ok()?;
+        let host: String = url.host_str()?.to_lowercase();
+        let site: &str = host
+            .strip_prefix("www.")
+            .or_else(|| host.strip_prefix("m."))
+            .unwrap_or(&host);
+        if !is_stackexchange_site(site) {
+            return None;
+        }
+        let site: String = site.to_string();
+        let anchor: Option<u64> = url.fragment().and_then(parse_id);
+        let segments: Vec<&str> = url
+            .path_segments()?
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        match segments.as_slice() {
+            ["questions", id, rest @ ..] => Some(QuestionLink {
+                site,
+                question_id: Some(parse_id(id)?),
+                slug: rest.first().map(|slug| slug.to_string()),
+                answer_id: rest.get(1).and_then(|id| parse_id(id)).or(anchor),
+            }),
+            // short link which is shared by users, like `/q/12345/678` (with user id).
+            ["q", id, ..] => Some(QuestionLink {
+                site,
+                question_id: Some(parse_id(id)?),
+                slug: None,
+                answer_id: anchor,
+            }),
+            ["a", id, ..] | ["answers", id, ..] => Some(QuestionLink {
+                site,
+                question_id: None,
+                slug: None,
+                answer_id: Some(parse_id(id)?),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn site(&self) -> &str {
+        &self.site
+    }
+
+    pub fn question_id(&self) -> Option<u64> {
+        self.question_id
+    }
+
+    pub fn slug(&self) -> Option<&str> {
+        self.slug.as_deref()
+    }
+
+    pub fn answer_id(&self) -> Option<u64> {
+        self.answer_id
+    }
+
+    /// Key which identifies the question, it's used to cache pages and dedup links.
+    ///
+    /// Slug and answer are not included, because they lead to the same question page.
+    pub fn cache_key(&self) -> String {
+        match self.question_id {
+            Some(question_id) => format!("https://{}/questions/{}", self.site, question_id),
+            None => format!(
+                "https://{}/a/{}",
+                self.site,
+                self.answer_id.unwrap_or_default()
+            ),
+        }
+    }
+}
+
+impl fmt::Display for QuestionLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let question_id: u64 = match self.question_id {
+            Some(question_id) => question_id,
+            None => return write!(f, "{}", self.cache_key()),
+        };
+        write!(f, "https://{}/questions/{}", self.site, question_id)?;
+        if let Some(slug) = &self.slug {
+            write!(f, "/{}", slug)?;
+        }
+        if let Some(answer_id) = self.answer_id {
+            write!(f, "#{}", answer_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Get the key which is used to cache page of the link.
+///
+/// Links to the same question share the same key, links which are not question are
+/// used as key directly.
+pub(crate) fn cache_key(link: &str) -> String {
+    QuestionLink::parse(link)
+        .map(|link| link.cache_key())
+        .unwrap_or_else(|| link.to_string())
+}
+
+fn is_stackexchange_site(host: &str) -> bool {
+    host.ends_with(".stackexchange.com")
+        || SITES
+            .iter()
+            .any(|site| host == *site || host.ends_with(&format!(".{}", site)))
+}
+
+fn parse_id(id: &str) -> Option<u64> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    id.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_question_link() {
+        let cases = [
+            (
+                "http://m.stackoverflow.com/questions/12345/parse-json?noredirect=1&lq=1",
+                "https://stackoverflow.com/questions/12345/parse-json",
+            ),
+            (
+                "https://www.stackoverflow.com/questions/12345",
+                "https://stackoverflow.com/questions/12345",
+            ),
+            (
+                "https://stackoverflow.com/questions/12345/parse-json/67890#67890",
+                "https://stackoverflow.com/questions/12345/parse-json#67890",
+            ),
+            (
+                "https://stackoverflow.com/questions/12345/parse-json#comment123_456",
+                "https://stackoverflow.com/questions/12345/parse-json",
+            ),
+            (
+                "https://stackoverflow.com/q/12345/678",
+                "https://stackoverflow.com/questions/12345",
+            ),
+            (
+                "https://unix.stackexchange.com/a/67890/123",
+                "https://unix.stackexchange.com/a/67890",
+            ),
+            (
+                "https://ru.stackoverflow.com/questions/12345/json",
+                "https://ru.stackoverflow.com/questions/12345/json",
+            ),
+        ];
+        for (link, expected) in cases.iter() {
+            assert_eq!(QuestionLink::parse(link).unwrap().to_string(), *expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_question_link() {
+        for link in [
+            "https://stackoverflow.com",
+            "https://stackoverflow.com/questions/tagged/rust",
+            "https://stackoverflow.com/users/12345/someone",
+            "https://example.com/questions/12345/parse-json",
+            "https://notstackoverflow.com/questions/12345",
+            "not a link",
+        ]
+        .iter()
+        {
+            assert_eq!(QuestionLink::parse(link), None);
+        }
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(
+            cache_key("https://stackoverflow.com/questions/12345/parse-js"),
+            cache_key("http://m.stackoverflow.com/questions/12345/parse-json#67890")
+        );
+        assert_eq!(
+            cache_key("https://stackoverflow.com/a/67890/123"),
+            "https://stackoverflow.com/a/67890"
+        );
+        assert_eq!(cache_key("https://example.com"), "https://example.com");
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! It can be used to make a paging output, or just output normally(dump to stdout).
 //!
+//! The pager command is taken from `$HORS_PAGER`, then the `pager` entry of the configuration
+//! file, then `$PAGER`, and `less` is used if none of them is given.  When the pager command
+//! can't be spawned (e.g: `less` is not installed), a simple built-in pager is used instead.
+//!
 //! # Examples
 //!
 //! Make paging output
@@ -30,12 +34,25 @@
 //! }
 //! ```
 use crate::config::PagingOption;
-use std::io::{self, Stdout, Write};
+use crate::search_config::SEARCH_CONFIG;
+use std::env;
+use std::io::{self, BufRead, IsTerminal, Stdout, Write};
+use std::path::Path;
 use std::process::{Child, Command, Stdio};
+use terminal_size::{terminal_size, Height};
+
+/// Pager command which is used when user doesn't specify any pager.
+const DEFAULT_PAGER: &str = "less";
+/// Arguments passed to `less` when user doesn't give any arguments to it.
+const LESS_DEFAULT_ARGS: [&str; 3] = ["--raw-control-chars", "--quit-if-one-screen", "--no-init"];
+/// Special pager name to use hors built-in pager.
+const BUILTIN_PAGER: &str = "builtin";
 
 pub enum Output {
     /// Paging output, along with a relative output handler process.
     Paging(Child),
+    /// Paging output through hors built-in pager.
+    BuiltinPaging(BuiltinPager),
     /// Normal output, along with stdout.
     Normal(Stdout),
 }
@@ -44,20 +61,41 @@ impl Output {
     pub fn new(option: &PagingOption) -> Output {
         // when paging option is never, we just make a normal output, in this case
         // result will output normally.
-        // If we need paging, use `less` command to handle paging feature for us.
+        // If we need paging, use pager command to handle paging feature for us.
         match option {
             PagingOption::Auto => {
-                // create a less process.
-                Command::new("less")
-                    .args(&["--raw-control-chars", "--quit-if-one-screen", "--no-init"])
-                    .stdin(Stdio::piped())
-                    .spawn()
-                    .map_or_else(|_| Output::Normal(io::stdout()), Output::Paging)
+                if io::stdout().is_terminal() {
+                    Output::paging()
+                } else {
+                    Output::Normal(io::stdout())
+                }
             }
+            PagingOption::Always => Output::paging(),
             PagingOption::Never => Output::Normal(io::stdout()),
         }
     }
 
+    fn paging() -> Output {
+        match pager_command() {
+            Some(PagerCommand::External { program, args }) => Command::new(&program)
+                .args(&args)
+                .stdin(Stdio::piped())
+                .spawn()
+                .map_or_else(
+                    |err| {
+                        debug!(
+                            "Can't spawn pager `{}`, fallback to builtin pager: {}",
+                            program, err
+                        );
+                        Output::BuiltinPaging(BuiltinPager::default())
+                    },
+                    Output::Paging,
+                ),
+            Some(PagerCommand::Builtin) => Output::BuiltinPaging(BuiltinPager::default()),
+            None => Output::Normal(io::stdout()),
+        }
+    }
+
     /// Get output handler so user can write data.
     pub fn get_handler(&mut self) -> &mut dyn Write {
         match self {
@@ -65,16 +103,211 @@ impl Output {
                 .stdin
                 .as_mut()
                 .expect("get stdin of child process failed"),
+            Output::BuiltinPaging(pager) => pager,
             Output::Normal(out) => out,
         }
     }
 }
 
-// Implement this method to make relative `less` command waiting for output.
+// Implement this method to make relative pager waiting for output.
 impl Drop for Output {
     fn drop(&mut self) {
-        if let Output::Paging(child_proc) = self {
-            let _ = child_proc.wait();
+        match self {
+            Output::Paging(child_proc) => {
+                let _ = child_proc.wait();
+            }
+            Output::BuiltinPaging(pager) => {
+                if let Err(err) = pager.page() {
+                    debug!("Builtin pager exits with error: {}", err);
+                }
+            }
+            Output::Normal(_) => {}
+        }
+    }
+}
+
+/// Pager which is used to page output.
+#[derive(Debug, PartialEq)]
+enum PagerCommand {
+    /// External pager program along with it's arguments.
+    External { program: String, args: Vec<String> },
+    /// Hors built-in pager.
+    Builtin,
+}
+
+/// Get pager command from `$HORS_PAGER`, config file, `$PAGER` in order.
+///
+/// # Returns
+///
+/// The pager to use, or None if user specify an empty pager, which means no paging.
+fn pager_command() -> Option<PagerCommand> {
+    let command: String = env::var("HORS_PAGER")
+        .ok()
+        .or_else(|| SEARCH_CONFIG.get_pager().map(String::from))
+        .or_else(|| env::var("PAGER").ok())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    debug!("Pager command: {:?}", command);
+    parse_pager(&command)
+}
+
+/// Parse pager command line into program and arguments.
+///
+/// If the program is `less` without any arguments, some default arguments are given to it
+/// so it can display colorized output and quit when output fits one screen.
+fn parse_pager(command: &str) -> Option<PagerCommand> {
+    let command = command.trim();
+    if command.is_empty() {
+        return None;
+    }
+    if command == BUILTIN_PAGER {
+        return Some(PagerCommand::Builtin);
+    }
+
+    let mut words: Vec<String> = shell_words::split(command).unwrap_or_else(|err| {
+        warn!("Can't parse pager command `{}`: {}", command, err);
+        vec![command.to_string()]
+    });
+    let program: String = words.remove(0);
+    let mut args: Vec<String> = words;
+    let is_less = Path::new(&program)
+        .file_stem()
+        .is_some_and(|stem| stem == DEFAULT_PAGER);
+    if is_less && args.is_empty() {
+        args = LESS_DEFAULT_ARGS
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+    }
+    Some(PagerCommand::External { program, args })
+}
+
+/// A simple pager which is used when no external pager is available.
+///
+/// It collects all output, and displays them page by page when it's dropped.  User can press
+/// `Enter` to see next page, or input `q` to quit.
+#[derive(Default)]
+pub struct BuiltinPager {
+    buffer: Vec<u8>,
+}
+
+impl BuiltinPager {
+    fn page(&self) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        let content = String::from_utf8_lossy(&self.buffer);
+        let lines: Vec<&str> = content.split_inclusive('\n').collect();
+
+        // we can only make paging when user can interact with us through terminal.
+        let page_size: usize = match terminal_size() {
+            Some((_, Height(height))) if stdout.is_terminal() && io::stdin().is_terminal() => {
+                (height as usize).saturating_sub(1).max(1)
+            }
+            _ => return stdout.write_all(content.as_bytes()),
+        };
+        if lines.len() <= page_size {
+            return stdout.write_all(content.as_bytes());
         }
+
+        let stdin = io::stdin();
+        let mut input = String::new();
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + page_size).min(lines.len());
+            for line in &lines[start..end] {
+                stdout.write_all(line.as_bytes())?;
+            }
+            if end == lines.len() {
+                break;
+            }
+            write!(
+                stdout,
+                "\x1b[7m--More--({}%)\x1b[0m [Enter: next page, q: quit] ",
+                end * 100 / lines.len()
+            )?;
+            stdout.flush()?;
+
+            input.clear();
+            let read_size = stdin.lock().read_line(&mut input)?;
+            // erase the prompt line, the cursor moves to next line after user press `Enter`.
+            write!(stdout, "\x1b[1A\x1b[2K")?;
+            if read_size == 0 || input.trim() == "q" {
+                break;
+            }
+            start = end;
+        }
+        stdout.flush()
+    }
+}
+
+impl Write for BuiltinPager {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pager_with_arguments() {
+        assert_eq!(
+            parse_pager("more -d"),
+            Some(PagerCommand::External {
+                program: "more".to_string(),
+                args: vec!["-d".to_string()]
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_pager_with_less_and_no_arguments() {
+        assert_eq!(
+            parse_pager("/usr/bin/less"),
+            Some(PagerCommand::External {
+                program: "/usr/bin/less".to_string(),
+                args: LESS_DEFAULT_ARGS
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_pager_with_quoted_arguments() {
+        assert_eq!(
+            parse_pager(r#"less -R --prompt "hors answers""#),
+            Some(PagerCommand::External {
+                program: "less".to_string(),
+                args: vec![
+                    "-R".to_string(),
+                    "--prompt".to_string(),
+                    "hors answers".to_string()
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_pager_builtin() {
+        assert_eq!(parse_pager("builtin"), Some(PagerCommand::Builtin));
+    }
+
+    #[test]
+    fn test_parse_pager_when_it_is_empty() {
+        assert_eq!(parse_pager("  "), None);
+    }
+
+    #[test]
+    fn test_builtin_pager_collects_output() {
+        let mut pager = BuiltinPager::default();
+        pager.write_all(b"test ").unwrap();
+        pager.write_all(b"data").unwrap();
+        assert_eq!(pager.buffer, b"test data".to_vec());
     }
 }
@@ -0,0 +1,443 @@
+//! Make search query from user input.
+//!
+//! Besides the query typed by user, hors can make query from compiler or runtime error
+//! output, so `cargo build 2>&1 | hors --from-error` searches for the error directly.
+//!
+//! When hors is run inside a project, the language of project is added into query, so
+//! user doesn't need to type `rust` in every query.
+//!
+//! Question links and ids like `so:12345` refer to a question directly, so searching
+//! can be skipped.
+
+use crate::link::QuestionLink;
+use crate::search_config::SEARCH_CONFIG;
+use regex::Regex;
+use std::env;
+use std::path::Path;
+
+/// Maximum number of words in query which is made from error output.
+const MAX_ERROR_QUERY_WORDS: usize = 16;
+
+/// Sites which can be referred by prefix of question id, like `so:12345`.
+const SITE_PREFIXES: [(&str, &str); 3] = [
+    ("so", "stackoverflow.com"),
+    ("su", "superuser.com"),
+    ("sf", "serverfault.com"),
+];
+
+/// Files which mark the project language, files which are more specific come first.
+const PROJECT_MARKERS: [(&str, &str); 19] = [
+    ("Cargo.toml", "rust"),
+    ("go.mod", "go"),
+    ("tsconfig.json", "typescript"),
+    ("package.json", "javascript"),
+    ("pyproject.toml", "python"),
+    ("setup.py", "python"),
+    ("requirements.txt", "python"),
+    ("Pipfile", "python"),
+    ("build.gradle.kts", "kotlin"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("build.sbt", "scala"),
+    ("Gemfile", "ruby"),
+    ("composer.json", "php"),
+    ("mix.exs", "elixir"),
+    ("stack.yaml", "haskell"),
+    ("pubspec.yaml", "dart"),
+    ("Package.swift", "swift"),
+    ("CMakeLists.txt", "c++"),
+];
+
+/// Words which mean that query is about a specific language or topic already, so project
+/// language shouldn't be added.
+///
+/// Names which are ordinary words or too short, like `go`, `c` and `sh`, are not
+/// included, because they appear in queries which are not about the language.
+const TOPIC_WORDS: [&str; 42] = [
+    "rust",
+    "cargo",
+    "golang",
+    "typescript",
+    "javascript",
+    "js",
+    "nodejs",
+    "npm",
+    "python",
+    "python3",
+    "pip",
+    "kotlin",
+    "java",
+    "maven",
+    "gradle",
+    "scala",
+    "ruby",
+    "rails",
+    "php",
+    "elixir",
+    "haskell",
+    "dart",
+    "flutter",
+    "swift",
+    "c++",
+    "cpp",
+    "c#",
+    "bash",
+    "zsh",
+    "powershell",
+    "git",
+    "docker",
+    "kubernetes",
+    "linux",
+    "sql",
+    "mysql",
+    "postgres",
+    "regex",
+    "vim",
+    "emacs",
+    "css",
+    "html",
+];
+
+lazy_static! {
+    /// rustc error with error code, like `error[E0382]: borrow of moved value: `a``.
+    static ref RUST_ERROR: Regex = Regex::new(r"error\[(E\d{4})\]: (.*)").unwrap();
+    /// the last line of python traceback, like `KeyError: 'name'`.
+    static ref PYTHON_EXCEPTION: Regex =
+        Regex::new(r"^(?:[a-zA-Z_]\w*\.)*([A-Z]\w*(?:Error|Exception|Exit|Interrupt|Warning))(?:: (.*))?$")
+            .unwrap();
+    /// java exception, like `Exception in thread "main" java.lang.NullPointerException: msg`.
+    static ref JAVA_EXCEPTION: Regex =
+        Regex::new(r"(?:^|\s)(?:[a-z]\w*\.)+([A-Z]\w*(?:Exception|Error))(?::\s*(.*))?$").unwrap();
+    /// other errors, like `main.c:3:5: error: expected ';' before '}' token`.
+    static ref GENERIC_ERROR: Regex = Regex::new(r"(?i)\b(?:fatal )?error\b:?\s*(.*)").unwrap();
+    /// file paths and urls, like `src/main.rs:3:5` or `C:\project\main.c`.
+    static ref PATH: Regex = Regex::new(r"\S*[/\\]\S*").unwrap();
+    /// location in file, like `main.c:3:5` or `line 42`.
+    static ref LOCATION: Regex = Regex::new(r"\S+:\d+(?::\d+)?|(?i)\bline \d+").unwrap();
+    /// hex addresses and numbers.
+    static ref NUMBER: Regex = Regex::new(r"\b0x[0-9a-fA-F]+\b|\b\d+\b").unwrap();
+    /// quotes around words, apostrophes inside words are kept.
+    static ref QUOTE: Regex = Regex::new(r#"[`"]|\B'|'\B"#).unwrap();
+}
+
+/// Make search query from compiler or runtime error output.
+///
+/// The meaningful part of error is extracted, like rustc error code, python exception
+/// type and java exception class name, paths and line numbers are stripped.
+///
+/// # Examples
+///
+/// ```rust
+/// use hors::query_from_error;
+///
+/// let output = "error[E0382]: borrow of moved value: `a`\n --> src/main.rs:4:20";
+/// assert_eq!(
+///     query_from_error(output),
+///     Some(String::from("rust E0382 borrow of moved value: a"))
+/// );
+/// ```
+///
+/// # Returns
+///
+/// The query, or None if nothing can be extracted.
+pub fn query_from_error(output: &str) -> Option<String> {
+    let lines: Vec<&str> = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    if let Some(caps) = lines.iter().find_map(|line| RUST_ERROR.captures(line)) {
+        return Some(make_query(&["rust", &caps[1], &caps[2]]));
+    }
+    if lines
+        .iter()
+        .any(|line| line.starts_with("Traceback (most recent call last)"))
+    {
+        // the exception is at the end of traceback.
+        if let Some(caps) = lines
+            .iter()
+            .rev()
+            .find_map(|line| PYTHON_EXCEPTION.captures(line))
+        {
+            let message: &str = caps.get(2).map_or("", |m| m.as_str());
+            return Some(make_query(&["python", &caps[1], message]));
+        }
+    }
+    // the root cause is the last `Caused by` exception.
+    if let Some(caps) = lines
+        .iter()
+        .rev()
+        .find_map(|line| JAVA_EXCEPTION.captures(line))
+    {
+        let message: &str = caps.get(2).map_or("", |m| m.as_str());
+        return Some(make_query(&["java", &caps[1], message]));
+    }
+    if let Some(caps) = lines
+        .iter()
+        .find_map(|line| PYTHON_EXCEPTION.captures(line))
+    {
+        let message: &str = caps.get(2).map_or("", |m| m.as_str());
+        return Some(make_query(&[&caps[1], message]));
+    }
+    if let Some(caps) = lines.iter().find_map(|line| GENERIC_ERROR.captures(line)) {
+        let query: String = make_query(&[&caps[1]]);
+        if !query.is_empty() {
+            return Some(query);
+        }
+    }
+    lines
+        .first()
+        .map(|line| make_query(&[line]))
+        .filter(|query| !query.is_empty())
+}
+
+/// Get question link from input which refers to a question directly.
+///
+/// The input can be a question or answer link of stackoverflow, superuser, serverfault
+/// and other stackexchange sites, or a question id with site prefix, like `so:12345`,
+/// `su:12345` and `sf:12345`.
+///
+/// # Examples
+///
+/// ```rust
+/// use hors::query::question_link;
+///
+/// assert_eq!(
+///     question_link("so:7771011"),
+///     Some(String::from("https://stackoverflow.com/questions/7771011"))
+/// );
+/// assert_eq!(question_link("how to parse json"), None);
+/// ```
+///
+/// # Returns
+///
+/// The canonical link, or None if input doesn't refer to a question.
+pub fn question_link(input: &str) -> Option<String> {
+    let input: &str = input.trim();
+    let is_id = |id: &str| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit());
+    if let Some((prefix, id)) = input.split_once(':') {
+        if let Some((_, site)) = SITE_PREFIXES.iter().find(|(name, _)| *name == prefix) {
+            return Some(format!("https://{}/questions/{}", site, id)).filter(|_| is_id(id));
+        }
+    }
+
+    QuestionLink::parse(input).map(|link| link.to_string())
+}
+
+/// Detect the language of project which the given directory belongs to.
+///
+/// The directory and its ancestors are checked for project files like `Cargo.toml`,
+/// the search stops at repository root (the directory which contains `.git`).
+///
+/// # Returns
+///
+/// The language name, like `rust`, or None if it's not in a project.
+pub fn detect_project_language(dir: &Path) -> Option<&'static str> {
+    for dir in dir.ancestors() {
+        for (marker, language) in PROJECT_MARKERS.iter() {
+            if dir.join(marker).exists() {
+                return Some(language);
+            }
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Add project language into query, unless query is about a language or topic already.
+///
+/// # Returns
+///
+/// The query, and the language which is used to guess syntax of code.  The language is
+/// None if query is about another language or topic.
+pub fn augment_query<'a>(query: &str, language: &'a str) -> (String, Option<&'a str>) {
+    let words: Vec<String> = query
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(str::to_lowercase)
+        .collect();
+    if words.iter().any(|word| word == language) {
+        return (query.to_string(), Some(language));
+    }
+    if words
+        .iter()
+        .any(|word| TOPIC_WORDS.contains(&word.as_str()))
+    {
+        return (query.to_string(), None);
+    }
+    (format!("{} {}", query, language), Some(language))
+}
+
+/// Add the language of project in current directory into query.
+///
+/// It can be disabled through `project_aware = false` in config file.
+///
+/// # Returns
+///
+/// The query, and the language which is used to guess syntax of code.
+pub fn augment_with_project(query: &str) -> (String, Option<&'static str>) {
+    if !SEARCH_CONFIG.is_project_aware() {
+        return (query.to_string(), None);
+    }
+    match env::current_dir()
+        .ok()
+        .and_then(|dir| detect_project_language(&dir))
+    {
+        Some(language) => augment_query(query, language),
+        None => (query.to_string(), None),
+    }
+}
+
+/// Join parts into query, paths, line numbers and quotes are stripped.
+fn make_query(parts: &[&str]) -> String {
+    let text: String = parts.join(" ");
+    let text = PATH.replace_all(&text, " ");
+    let text = LOCATION.replace_all(&text, " ");
+    let text = NUMBER.replace_all(&text, " ");
+    // quotes are removed rather than replaced, so no space is left inside brackets.
+    let text = QUOTE.replace_all(&text, "");
+    text.split_whitespace()
+        .take(MAX_ERROR_QUERY_WORDS)
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_project_language() {
+        let root = env::temp_dir().join(format!("hors_project_test_{}", std::process::id()));
+        let src = root.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        assert_eq!(detect_project_language(&src), None);
+        std::fs::write(root.join("Cargo.toml"), "").unwrap();
+        std::fs::write(root.join("package.json"), "").unwrap();
+        let language = detect_project_language(&src);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(language, Some("rust"));
+    }
+
+    #[test]
+    fn test_augment_query() {
+        assert_eq!(
+            augment_query("parse json", "rust"),
+            (String::from("parse json rust"), Some("rust"))
+        );
+        assert_eq!(
+            augment_query("Rust parse json", "rust"),
+            (String::from("Rust parse json"), Some("rust"))
+        );
+        assert_eq!(
+            augment_query("undo git commit", "rust"),
+            (String::from("undo git commit"), None)
+        );
+        assert_eq!(
+            augment_query("python parse json", "rust"),
+            (String::from("python parse json"), None)
+        );
+        assert_eq!(
+            augment_query("how to go back in history", "python"),
+            (
+                String::from("how to go back in history python"),
+                Some("python")
+            )
+        );
+    }
+
+    #[test]
+    fn test_question_link() {
+        assert_eq!(
+            question_link("su:12345"),
+            Some(String::from("https://superuser.com/questions/12345"))
+        );
+        assert_eq!(
+            question_link("sf:12345"),
+            Some(String::from("https://serverfault.com/questions/12345"))
+        );
+        assert_eq!(question_link("so:abc"), None);
+        assert_eq!(
+            question_link("https://stackoverflow.com/questions/12345/parse-json?noredirect=1"),
+            Some(String::from(
+                "https://stackoverflow.com/questions/12345/parse-json"
+            ))
+        );
+        assert_eq!(
+            question_link("https://unix.stackexchange.com/a/678/90"),
+            Some(String::from("https://unix.stackexchange.com/a/678"))
+        );
+        assert_eq!(
+            question_link("https://stackoverflow.com/questions/tagged/rust"),
+            None
+        );
+        assert_eq!(question_link("https://example.com/questions/12345"), None);
+        assert_eq!(question_link("error: expected ;"), None);
+    }
+
+    #[test]
+    fn test_query_from_rust_error() {
+        let output = r#"   Compiling demo v0.1.0 (/home/user/demo)
+error[E0502]: cannot borrow `v` as mutable because it is also borrowed as immutable
+ --> src/main.rs:4:5
+  |
+3 |     let first = &v[0];
+  |                  - immutable borrow occurs here
+"#;
+        assert_eq!(
+            query_from_error(output),
+            Some(String::from(
+                "rust E0502 cannot borrow v as mutable because it is also borrowed as immutable"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_query_from_python_traceback() {
+        let output = r#"Traceback (most recent call last):
+  File "/home/user/app.py", line 3, in <module>
+    import numpy
+ModuleNotFoundError: No module named 'numpy'
+"#;
+        assert_eq!(
+            query_from_error(output),
+            Some(String::from(
+                "python ModuleNotFoundError No module named numpy"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_query_from_java_exception() {
+        let output = r#"Exception in thread "main" java.lang.RuntimeException: wrapped
+	at com.example.App.main(App.java:10)
+Caused by: java.lang.NullPointerException: Cannot invoke "String.length()" because "s" is null
+	at com.example.App.run(App.java:20)
+"#;
+        assert_eq!(
+            query_from_error(output),
+            Some(String::from(
+                "java NullPointerException Cannot invoke String.length() because s is null"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_query_from_generic_error() {
+        let output = "main.c:3:5: error: expected ';' before '}' token\n";
+        assert_eq!(
+            query_from_error(output),
+            Some(String::from("expected ; before } token"))
+        );
+        assert_eq!(
+            query_from_error("TypeError: Cannot read properties of undefined (reading 'map')"),
+            Some(String::from(
+                "TypeError Cannot read properties of undefined (reading map)"
+            ))
+        );
+        assert_eq!(query_from_error("\n  \n"), None);
+    }
+}
@@ -22,9 +22,19 @@ fn load_config() -> SearchConfig {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default)]
 pub struct SearchConfig {
+    /// pager command which is used to page output, e.g: "less -R".
+    #[serde(default)]
+    pager: Option<String>,
+    /// banner line which is used to split answers.
+    #[serde(default)]
+    splitter: Option<String>,
+    #[serde(default)]
     engine_domain: EngineDomain,
+    /// add language of the current project into query, it's enabled by default.
+    #[serde(default)]
+    project_aware: Option<bool>,
 }
 
 impl SearchConfig {
@@ -39,6 +49,18 @@ impl SearchConfig {
     pub fn get_google_domain(&self) -> &str {
         &self.engine_domain.google
     }
+
+    pub fn get_pager(&self) -> Option<&str> {
+        self.pager.as_deref()
+    }
+
+    pub fn get_splitter(&self) -> Option<&str> {
+        self.splitter.as_deref()
+    }
+
+    pub fn is_project_aware(&self) -> bool {
+        self.project_aware.unwrap_or(true)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -51,14 +73,6 @@ pub struct EngineDomain {
     google: String,
 }
 
-impl Default for SearchConfig {
-    fn default() -> SearchConfig {
-        SearchConfig {
-            engine_domain: Default::default(),
-        }
-    }
-}
-
 impl Default for EngineDomain {
     fn default() -> EngineDomain {
         EngineDomain {
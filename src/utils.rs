@@ -1,5 +1,11 @@
+use crate::error::{Error, Result};
+use directories::BaseDirs;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 
 static USER_AGENTS: [&str; 6] =
     [
@@ -28,6 +34,45 @@ pub fn random_agent() -> &'static str {
     }
 }
 
+/// Get hors cache directory, like `$HOME/.cache/hors` on linux.
+pub fn cache_dir() -> Result<PathBuf> {
+    match BaseDirs::new() {
+        Some(base_dirs) => {
+            let mut dir = base_dirs.cache_dir().to_path_buf();
+            dir.push("hors");
+            Ok(dir)
+        }
+        None => Err(Error::from_parse("get cache dir failed.")),
+    }
+}
+
+/// Get hors data directory, like `$HOME/.local/share/hors` on linux.
+///
+/// Different from cache directory, data in it is kept when cache is cleared.
+pub fn data_dir() -> Result<PathBuf> {
+    match BaseDirs::new() {
+        Some(base_dirs) => {
+            let mut dir = base_dirs.data_dir().to_path_buf();
+            dir.push("hors");
+            Ok(dir)
+        }
+        None => Err(Error::from_parse("get data dir failed.")),
+    }
+}
+
+/// Read data from the given json file.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let f = File::open(path)?;
+    serde_json::from_reader(f).map_err(|_| Error::from_parse("Deserialize json file failed"))
+}
+
+/// Write data into the given json file, the file is truncated if it's existed.
+pub fn write_json<T: Serialize>(path: &Path, data: &T) -> Result<()> {
+    let f = File::create(path)?;
+    serde_json::to_writer_pretty(f, data)
+        .map_err(|_| Error::from_parse("Serialize json file failed"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;